@@ -21,6 +21,76 @@ pub mod covenant {
         protocol.total_staked = 0;
         protocol.total_slashed = 0;
         protocol.bump = ctx.bumps.protocol;
+        protocol.stake_warning_threshold_bps = 0;
+        protocol.violation_weights = DEFAULT_VIOLATION_WEIGHTS;
+        protocol.overcollateralization_bonus_cap_bps = DEFAULT_OVERCOLLATERALIZATION_BONUS_CAP_BPS;
+        protocol.settlement_discount_bps = DEFAULT_SETTLEMENT_DISCOUNT_BPS;
+        protocol.authorities = Vec::new();
+        protocol.threshold = 0;
+        protocol.min_reputation_to_serve = 0;
+        protocol.evidence_ttl_seconds = DEFAULT_EVIDENCE_TTL_SECONDS;
+        protocol.protected_balance = 0;
+        protocol.onboarding_shield_seconds = 0;
+        protocol.max_description_len = DEFAULT_DESCRIPTION_LEN;
+        protocol.first_reporter_bonus_bps = 0;
+        protocol.self_dealing_checks = true;
+        protocol.violation_decay_seconds = 0;
+        protocol.min_successes_for_sla = 0;
+        protocol.evidence_uri_required = [false; 5];
+        protocol.paused = false;
+        protocol.circuit_breaker_threshold = 0;
+        protocol.slashed_in_current_window = 0;
+        protocol.window_start = 0;
+        protocol.config_manager = Pubkey::default();
+        protocol.season = 0;
+        protocol.season_start = Clock::get()?.unix_timestamp;
+        protocol.season_carryover_bps = DEFAULT_SEASON_CARRYOVER_BPS;
+        protocol.slashing_frozen = false;
+        protocol.crank_reward = 0;
+        protocol.crank_budget = 0;
+        protocol.crank_cooldown_seconds = 0;
+        protocol.unique_names = false;
+        protocol.duration_penalty_bps_per_hour = 0;
+        protocol.max_duration_penalty_bps = 10_000;
+        protocol.max_reporter_compensation = 0;
+        protocol.min_sla_update_interval = 0;
+        protocol.max_single_slash_bps = 10_000;
+        protocol.min_slash_amount = 0;
+        protocol.onboarding_mode = OnboardingMode::Open;
+        protocol.cure_window_seconds = 0;
+        protocol.cure_window_by_type = [0; 5];
+        protocol.cure_escalation_threshold = 0;
+        protocol.min_distinct_reporters = 0;
+        protocol.false_attestation_penalty_bps = 0;
+        protocol.slashed_rolling = 0;
+        protocol.rolling_window_start = 0;
+        protocol.rolling_window_seconds = DEFAULT_ROLLING_WINDOW_SECONDS;
+        protocol.withdrawal_tier_reputations = [0; WITHDRAWAL_TIERS];
+        protocol.max_withdrawal_per_period = [0; WITHDRAWAL_TIERS];
+        protocol.withdrawal_period_seconds = DEFAULT_WITHDRAWAL_PERIOD_SECONDS;
+        protocol.report_fee = 0;
+        protocol.dismissal_refund_bps = 0;
+        protocol.honest_pool_bps = 0;
+        protocol.honest_pool_accrued = 0;
+        protocol.honest_pool_weight = 0;
+        protocol.honest_payout = 0;
+        protocol.honest_payout_weight = 0;
+        protocol.honest_payout_remaining = 0;
+
+        // Fund the treasury's rent-exempt reserve so small fees can always land in it
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.treasury.lamports());
+        if rent_shortfall > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, rent_shortfall)?;
+        }
 
         msg!("Covenant Protocol initialized");
         Ok(())
@@ -31,41 +101,67 @@ pub mod covenant {
         ctx: Context<RegisterProvider>,
         name: String,
         service_endpoint: String,
+        endpoint_version: u16,
         stake_amount: u64,
+        region: [u8; 2],                // ISO-like region code, e.g. b"EU"
     ) -> Result<()> {
-        require!(name.len() <= 64, CovenantError::NameTooLong);
-        require!(service_endpoint.len() <= 256, CovenantError::EndpointTooLong);
-        require!(stake_amount >= MIN_STAKE, CovenantError::InsufficientStake);
+        open_provider(
+            ctx.accounts,
+            &ctx.bumps,
+            name,
+            service_endpoint,
+            endpoint_version,
+            stake_amount,
+            region,
+        )
+    }
 
-        // Transfer stake to vault
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.provider_authority.to_account_info(),
-                to: ctx.accounts.stake_vault.to_account_info(),
-            },
+    /// Register as a service provider and define its SLA in one atomic transaction,
+    /// so the provider is never live without an SLA. Unavailable while registrations
+    /// need approval, since a pending provider can't define an SLA.
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_with_sla(
+        ctx: Context<RegisterWithSLA>,
+        name: String,
+        service_endpoint: String,
+        endpoint_version: u16,
+        stake_amount: u64,
+        region: [u8; 2],
+        uptime_guarantee: u8,
+        max_response_time_ms: u32,
+        accuracy_guarantee: u8,
+        penalty_percentage: u8,
+        locked_until: i64,
+    ) -> Result<()> {
+        open_provider(
+            &mut ctx.accounts.register,
+            &ctx.bumps.register,
+            name,
+            service_endpoint,
+            endpoint_version,
+            stake_amount,
+            region,
+        )?;
+        // A brand-new provider has no track record to show
+        require!(
+            ctx.accounts.register.protocol.min_successes_for_sla == 0,
+            CovenantError::InsufficientTrackRecord
+        );
+        require!(
+            ctx.accounts.register.provider.status != ProviderStatus::PendingApproval,
+            CovenantError::ProviderPendingApproval
         );
-        system_program::transfer(cpi_context, stake_amount)?;
-
-        // Initialize provider account
-        let provider = &mut ctx.accounts.provider;
-        provider.authority = ctx.accounts.provider_authority.key();
-        provider.name = name;
-        provider.service_endpoint = service_endpoint;
-        provider.stake_amount = stake_amount;
-        provider.violations = 0;
-        provider.successful_requests = 0;
-        provider.created_at = Clock::get()?.unix_timestamp;
-        provider.is_active = true;
-        provider.bump = ctx.bumps.provider;
-
-        // Update protocol stats
-        let protocol = &mut ctx.accounts.protocol;
-        protocol.total_providers += 1;
-        protocol.total_staked += stake_amount;
 
-        msg!("Provider registered with {} lamports staked", stake_amount);
-        Ok(())
+        write_sla(
+            &mut ctx.accounts.sla,
+            ctx.accounts.register.provider.key(),
+            ctx.bumps.sla,
+            uptime_guarantee,
+            max_response_time_ms,
+            accuracy_guarantee,
+            penalty_percentage,
+            locked_until,
+        )
     }
 
     /// Define SLA terms for a service
@@ -75,51 +171,373 @@ pub mod covenant {
         max_response_time_ms: u32,      // Max response time in milliseconds
         accuracy_guarantee: u8,          // Percentage (0-100)
         penalty_percentage: u8,          // Percentage of stake to slash per violation
+        locked_until: i64,               // Terms can't change before this time (0 for no lock)
     ) -> Result<()> {
-        require!(uptime_guarantee <= 100, CovenantError::InvalidPercentage);
-        require!(accuracy_guarantee <= 100, CovenantError::InvalidPercentage);
-        require!(penalty_percentage > 0 && penalty_percentage <= 100, CovenantError::InvalidPercentage);
+        require!(
+            ctx.accounts.provider.status != ProviderStatus::Banned,
+            CovenantError::ProviderBlacklisted
+        );
+        require!(
+            ctx.accounts.provider.status != ProviderStatus::Probation,
+            CovenantError::ProviderOnProbation
+        );
+        require!(
+            ctx.accounts.provider.status != ProviderStatus::PendingApproval,
+            CovenantError::ProviderPendingApproval
+        );
+        require!(
+            ctx.accounts.provider.successful_requests >= ctx.accounts.protocol.min_successes_for_sla,
+            CovenantError::InsufficientTrackRecord
+        );
+
+        write_sla(
+            &mut ctx.accounts.sla,
+            ctx.accounts.provider.key(),
+            ctx.bumps.sla,
+            uptime_guarantee,
+            max_response_time_ms,
+            accuracy_guarantee,
+            penalty_percentage,
+            locked_until,
+        )
+    }
 
+    /// Raise the SLA's penalty, effective immediately. Cancels any scheduled decrease.
+    pub fn increase_penalty(ctx: Context<UpdateSLA>, penalty_percentage: u8) -> Result<()> {
         let sla = &mut ctx.accounts.sla;
-        sla.provider = ctx.accounts.provider.key();
-        sla.uptime_guarantee = uptime_guarantee;
-        sla.max_response_time_ms = max_response_time_ms;
-        sla.accuracy_guarantee = accuracy_guarantee;
+        let now = Clock::get()?.unix_timestamp;
+        sla.record_update(now, ctx.accounts.protocol.min_sla_update_interval)?;
+        sla.settle_penalty(now);
+        require!(
+            penalty_percentage > sla.penalty_percentage && penalty_percentage <= 100,
+            CovenantError::InvalidPercentage
+        );
+
         sla.penalty_percentage = penalty_percentage;
-        sla.created_at = Clock::get()?.unix_timestamp;
-        sla.is_active = true;
-        sla.bump = ctx.bumps.sla;
+        sla.pending_penalty_percentage = 0;
+        sla.penalty_decrease_effective_at = 0;
+
+        emit!(PenaltyIncreased {
+            sla: sla.key(),
+            penalty_percentage,
+        });
+        msg!("SLA penalty increased to {}%", penalty_percentage);
+        Ok(())
+    }
 
-        msg!("SLA defined: {}% uptime, {}ms response, {}% accuracy",
-             uptime_guarantee, max_response_time_ms, accuracy_guarantee);
+    /// Schedule a lower SLA penalty. Consumers get PENALTY_DECREASE_NOTICE_SECONDS of notice
+    /// before it applies; until then the current penalty stays in force.
+    pub fn decrease_penalty(ctx: Context<UpdateSLA>, penalty_percentage: u8) -> Result<()> {
+        let sla = &mut ctx.accounts.sla;
+        let now = Clock::get()?.unix_timestamp;
+        sla.record_update(now, ctx.accounts.protocol.min_sla_update_interval)?;
+        sla.settle_penalty(now);
+        require!(
+            penalty_percentage > 0 && penalty_percentage < sla.penalty_percentage,
+            CovenantError::InvalidPercentage
+        );
+
+        sla.pending_penalty_percentage = penalty_percentage;
+        sla.penalty_decrease_effective_at = now + PENALTY_DECREASE_NOTICE_SECONDS;
+
+        emit!(PenaltyDecreaseScheduled {
+            sla: sla.key(),
+            penalty_percentage,
+            effective_at: sla.penalty_decrease_effective_at,
+        });
+        msg!("SLA penalty decrease to {}% scheduled", penalty_percentage);
+        Ok(())
+    }
+
+    /// Withdraw the SLA's guarantees from now on. Breaches that occurred while it was active
+    /// can still be reported and slashed under it; later ones fall outside its window.
+    pub fn deactivate_sla(ctx: Context<UpdateSLA>) -> Result<()> {
+        let sla = &mut ctx.accounts.sla;
+        let now = Clock::get()?.unix_timestamp;
+        sla.require_unlocked(now)?;
+        sla.is_active = false;
+        sla.deactivated_at = now;
+
+        emit!(SLADeactivated {
+            sla: sla.key(),
+            deactivated_at: sla.deactivated_at,
+        });
+        msg!("SLA deactivated");
         Ok(())
     }
 
     /// Report an SLA violation (can be called by monitors or affected parties)
+    #[allow(clippy::too_many_arguments)]
     pub fn report_violation(
         ctx: Context<ReportViolation>,
         violation_type: ViolationType,
         evidence_hash: [u8; 32],        // Hash of off-chain evidence
         description: String,
+        evidence_uri: Option<String>,   // Where the evidence behind `evidence_hash` is stored
+        claims: Vec<u8>,                // Individual claim codes bundled in the report
+        occurred_at: i64,               // When the breach started (may predate the report)
+        ended_at: i64,                  // When the breach ended
+    ) -> Result<()> {
+        require!(
+            description.len() <= ctx.accounts.protocol.max_description_len as usize,
+            CovenantError::DescriptionTooLong
+        );
+        require!(!ctx.accounts.protocol.paused, CovenantError::ProtocolPaused);
+        // A banned provider can still be reported for breaches that hold back its withdrawal
+        require!(
+            ctx.accounts.provider.is_serving()
+                || ctx.accounts.provider.status == ProviderStatus::Banned,
+            CovenantError::ProviderInactive
+        );
+        require!(evidence_hash != [0; 32], CovenantError::MissingEvidence);
+        let evidence_uri = evidence_uri.unwrap_or_default();
+        require!(evidence_uri.len() <= MAX_EVIDENCE_URI_LEN, CovenantError::EvidenceUriTooLong);
+        require!(claims.len() <= MAX_CLAIMS, CovenantError::TooManyClaims);
+        require!(
+            claims.iter().enumerate().all(|(i, code)| !claims[..i].contains(code)),
+            CovenantError::InvalidClaim
+        );
+        require!(
+            !ctx.accounts.protocol.evidence_uri_required[violation_type as usize]
+                || !evidence_uri.is_empty(),
+            CovenantError::EvidenceRequired
+        );
+        // Suspected self-dealing has to go through the authority's emergency_report instead
+        require!(
+            !ctx.accounts.protocol.self_dealing_checks
+                || !is_self_dealing(&ctx.accounts.reporter.key(), &ctx.accounts.provider),
+            CovenantError::SelfDealing
+        );
+
+        // The breach must have happened while the SLA was in force, not in the future
+        let now = Clock::get()?.unix_timestamp;
+        require!(occurred_at <= now && ended_at <= now, CovenantError::InvalidOccurrenceTime);
+        require!(ended_at >= occurred_at, CovenantError::InvalidViolationWindow);
+        require!(ctx.accounts.sla.covers(occurred_at), CovenantError::OutsideSLAWindow);
+
+        // Declared maintenance at the time of the breach excuses downtime, but not other kinds of violation
+        if matches!(
+            violation_type,
+            ViolationType::UptimeViolation | ViolationType::ServiceUnavailable
+        ) {
+            let maintenance = &ctx.accounts.maintenance;
+            if maintenance.owner == ctx.program_id && !maintenance.data_is_empty() {
+                let schedule =
+                    MaintenanceWindow::try_deserialize(&mut &maintenance.try_borrow_data()?[..])?;
+                require!(!schedule.is_active_at(occurred_at), CovenantError::InMaintenanceWindow);
+            }
+        }
+
+        // The rent comes from the rent sponsor, else the provider's allowance for this
+        // reporter, else the reporter; the fee from the allowance, else the reporter. A
+        // sponsored reporter needs no lamports of its own.
+        let violation_account = ctx.accounts.violation.to_account_info();
+        let rent = Rent::get()?
+            .minimum_balance(VIOLATION_SPACE)
+            .saturating_sub(violation_account.lamports());
+        let report_fee = ctx.accounts.protocol.report_fee;
+        if let Some(rent_sponsor) = &ctx.accounts.rent_sponsor {
+            transfer_lamports(&ctx.accounts.system_program, rent_sponsor, &violation_account, rent)?;
+        }
+        if let Some(allowance) = &mut ctx.accounts.reporter_allowance {
+            let allowance_rent = if ctx.accounts.rent_sponsor.is_none() { rent } else { 0 };
+            let cost = allowance_rent.saturating_add(report_fee);
+            let reserve = Rent::get()?.minimum_balance(allowance.to_account_info().data_len());
+            require!(
+                allowance.get_lamports().saturating_sub(reserve) >= cost,
+                CovenantError::InsufficientAllowance
+            );
+            allowance.sub_lamports(cost)?;
+            violation_account.add_lamports(allowance_rent)?;
+            ctx.accounts.treasury.add_lamports(report_fee)?;
+            allowance.spent = allowance.spent.saturating_add(cost);
+        } else {
+            if ctx.accounts.rent_sponsor.is_none() {
+                transfer_lamports(&ctx.accounts.system_program, &ctx.accounts.reporter, &violation_account, rent)?;
+            }
+            // Anti-spam fee, routed to the protocol treasury
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.reporter,
+                &ctx.accounts.treasury.to_account_info(),
+                report_fee,
+            )?;
+        }
+
+        let provider_key = ctx.accounts.provider.key();
+        let index = ctx.accounts.provider.violations.to_le_bytes();
+        let violation_seeds = &[b"violation".as_ref(), provider_key.as_ref(), &index, &[ctx.bumps.violation]];
+        allocate_pda(&violation_account, VIOLATION_SPACE, &[&violation_seeds[..]], &ctx.accounts.system_program)?;
+
+        // The first report of a type not yet seen against this provider earns a bonus at slash time
+        let first_of_type = ctx
+            .accounts
+            .provider
+            .record_violation(violation_type, &ctx.accounts.protocol)?;
+        ctx.accounts.provider.note_reporter(ctx.accounts.reporter.key());
+
+        let violation = Violation {
+            provider: provider_key,
+            sla: ctx.accounts.sla.key(),
+            reporter: ctx.accounts.reporter.key(),
+            violation_type,
+            evidence_hash,
+            description,
+            evidence_uri,
+            claims,
+            settled_claims: 0,
+            occurred_at,
+            ended_at,
+            timestamp: now,
+            is_resolved: false,
+            bump: ctx.bumps.violation,
+            is_emergency: false,
+            first_of_type,
+            cure_deadline: now.saturating_add(escalated_cure_window(
+                ctx.accounts.protocol.cure_window_for(violation_type),
+                ctx.accounts.provider.disputes_lost,
+                ctx.accounts.protocol.cure_escalation_threshold,
+            )),
+            cure_submitted: false,
+            cure_evidence_hash: [0u8; 32],
+            is_cured: false,
+            evidence_valid_until: evidence_expiry(now, ctx.accounts.protocol.evidence_ttl_seconds),
+            // The rent is refunded on close to whoever bore it
+            rent_payer: match &ctx.accounts.rent_sponsor {
+                Some(rent_sponsor) => rent_sponsor.key(),
+                None if ctx.accounts.reporter_allowance.is_some() => ctx.accounts.provider.authority,
+                None => ctx.accounts.reporter.key(),
+            },
+            // A good-faith dismissal refunds part of the fee to whoever paid it
+            report_fee,
+            fee_payer: match &ctx.accounts.reporter_allowance {
+                Some(_) => ctx.accounts.provider.authority,
+                None => ctx.accounts.reporter.key(),
+            },
+        };
+        violation.try_serialize(&mut &mut violation_account.try_borrow_mut_data()?[..])?;
+
+        let reporter_record = &mut ctx.accounts.reporter_record;
+        reporter_record.reporter = ctx.accounts.reporter.key();
+        reporter_record.bump = ctx.bumps.reporter_record;
+
+        emit!(ViolationReported {
+            violation: ctx.accounts.violation.key(),
+            provider: ctx.accounts.provider.key(),
+            reporter: ctx.accounts.reporter.key(),
+            violation_type,
+            report_fee,
+        });
+        msg!("Violation reported against provider");
+        Ok(())
+    }
+
+    /// Invite scrutiny by pre-funding a named monitor's reports (provider authority). Each
+    /// report the monitor files against this provider is paid for from the allowance.
+    pub fn sponsor_reporter(ctx: Context<SponsorReporter>, reporter: Pubkey, amount: u64) -> Result<()> {
+        require!(amount > 0, CovenantError::InvalidAllowanceAmount);
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.allowance.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+
+        let allowance = &mut ctx.accounts.allowance;
+        if allowance.provider == Pubkey::default() {
+            ctx.accounts.provider.sponsorships += 1;
+        }
+        allowance.provider = ctx.accounts.provider.key();
+        allowance.reporter = reporter;
+        allowance.funded = allowance.funded.saturating_add(amount);
+        allowance.bump = ctx.bumps.allowance;
+
+        let reporter_record = &mut ctx.accounts.reporter_record;
+        reporter_record.reporter = reporter;
+        reporter_record.bump = ctx.bumps.reporter_record;
+
+        emit!(ReporterSponsored {
+            provider: allowance.provider,
+            reporter,
+            amount,
+        });
+        msg!("Sponsored reporter with {} lamports", amount);
+        Ok(())
+    }
+
+    /// Withdraw a reporter sponsorship, returning what's left of the allowance (provider authority)
+    pub fn revoke_sponsorship(ctx: Context<RevokeSponsorship>) -> Result<()> {
+        ctx.accounts.provider.sponsorships -= 1;
+        msg!("Reporter sponsorship revoked");
+        Ok(())
+    }
+
+    /// Authority-only fast path for emergencies (e.g. an active exploit).
+    /// The violation is flagged `is_emergency` for extra scrutiny.
+    pub fn emergency_report(
+        ctx: Context<EmergencyReport>,
+        violation_type: ViolationType,
+        evidence_hash: [u8; 32],
+        description: String,
     ) -> Result<()> {
-        require!(description.len() <= 512, CovenantError::DescriptionTooLong);
-        require!(ctx.accounts.provider.is_active, CovenantError::ProviderInactive);
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(
+            description.len() <= ctx.accounts.protocol.max_description_len as usize,
+            CovenantError::DescriptionTooLong
+        );
+        require!(
+            ctx.accounts.provider.is_serving()
+                || ctx.accounts.provider.status == ProviderStatus::Banned,
+            CovenantError::ProviderInactive
+        );
 
         let violation = &mut ctx.accounts.violation;
         violation.provider = ctx.accounts.provider.key();
-        violation.reporter = ctx.accounts.reporter.key();
+        violation.sla = ctx.accounts.sla.key();
+        violation.reporter = ctx.accounts.authority.key();
         violation.violation_type = violation_type;
         violation.evidence_hash = evidence_hash;
         violation.description = description;
+        violation.evidence_uri = String::new();
+        violation.claims = Vec::new();
+        violation.settled_claims = 0;
         violation.timestamp = Clock::get()?.unix_timestamp;
+        violation.occurred_at = violation.timestamp;
+        violation.ended_at = violation.timestamp;
         violation.is_resolved = false;
         violation.bump = ctx.bumps.violation;
+        violation.is_emergency = true;
+        violation.first_of_type = false;
+        // Emergency reports bypass the cure window
+        violation.cure_deadline = violation.timestamp;
+        violation.cure_submitted = false;
+        violation.cure_evidence_hash = [0u8; 32];
+        violation.is_cured = false;
+        violation.evidence_valid_until =
+            evidence_expiry(violation.timestamp, ctx.accounts.protocol.evidence_ttl_seconds);
+        violation.rent_payer = ctx.accounts.authority.key();
+        violation.report_fee = 0;
+        violation.fee_payer = ctx.accounts.authority.key();
 
-        // Increment provider violations
-        let provider = &mut ctx.accounts.provider;
-        provider.violations += 1;
+        let reporter_record = &mut ctx.accounts.reporter_record;
+        reporter_record.reporter = ctx.accounts.authority.key();
+        reporter_record.bump = ctx.bumps.reporter_record;
 
-        msg!("Violation reported against provider");
+        ctx.accounts
+            .provider
+            .record_violation(violation_type, &ctx.accounts.protocol)?;
+        ctx.accounts.provider.note_reporter(ctx.accounts.authority.key());
+
+        emit!(EmergencyViolationReported {
+            violation: violation.key(),
+            provider: violation.provider,
+            reporter: violation.reporter,
+            violation_type,
+        });
+        msg!("Emergency violation reported against provider");
         Ok(())
     }
 
@@ -130,66 +548,500 @@ pub mod covenant {
         let sla = &ctx.accounts.sla;
         let protocol = &mut ctx.accounts.protocol;
 
-        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(!protocol.paused, CovenantError::ProtocolPaused);
+        require!(!protocol.slashing_frozen, CovenantError::SlashingFrozen);
+        let now = Clock::get()?.unix_timestamp;
+        violation.require_slashable(now)?;
+        require!(
+            !provider.onboarding_shielded(protocol, now),
+            CovenantError::OnboardingShieldActive
+        );
         require!(provider.stake_amount > 0, CovenantError::NoStakeToSlash);
 
         // Calculate slash amount
+        let slash_amount = violation_penalty(
+            provider.stake_amount,
+            sla.effective_penalty(now),
+            violation.duration_seconds(),
+            violation.first_of_type,
+            protocol,
+        );
+        // A breach inside a period the provider attested as compliant is aggravated
+        let slash_amount = match &ctx.accounts.attestation {
+            Some(attestation) if attestation.contradicted_by(violation, sla) => {
+                let extra_penalty = false_attestation_penalty(slash_amount, protocol);
+                emit!(AttestationContradicted {
+                    attestation: attestation.key(),
+                    violation: violation.key(),
+                    provider: provider.key(),
+                    extra_penalty,
+                });
+                slash_amount.saturating_add(extra_penalty)
+            }
+            _ => slash_amount,
+        };
+        // Claims already settled by the provider aren't charged again
+        let slash_amount = violation.claims_share(slash_amount, violation.open_claims());
+        violation.settled_claims = violation.all_claims_mask();
+
+        let actual_slash = execute_slash(
+            protocol,
+            provider,
+            ctx.accounts.parent_provider.as_mut(),
+            violation,
+            slash_amount,
+            &ctx.accounts.system_program,
+            &ctx.accounts.stake_vault,
+            ctx.bumps.stake_vault,
+            ctx.accounts.reporter.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.honest_pool.to_account_info(),
+        )?;
+        if violation.is_resolved {
+            ctx.accounts.reporter_record.confirmed_reports += 1;
+        }
+
+        msg!("Slashed {} lamports from provider", actual_slash);
+        Ok(())
+    }
+
+    /// Provider admits fault on an unresolved violation and settles it immediately
+    /// at a reduced penalty of `penalty_percentage * settlement_discount_bps / 10000`.
+    ///
+    /// For a report bundling several claims, `acknowledged_claims` picks the admitted
+    /// subset (empty admits every open claim). Only that share of the penalty is charged;
+    /// the disputed claims stay open and can still be slashed.
+    pub fn acknowledge_violation(
+        ctx: Context<AcknowledgeViolation>,
+        acknowledged_claims: Vec<u8>,
+    ) -> Result<()> {
+        let violation = &mut ctx.accounts.violation;
+        let provider = &mut ctx.accounts.provider;
+        let sla = &ctx.accounts.sla;
+        let protocol = &mut ctx.accounts.protocol;
+
+        require!(!protocol.paused, CovenantError::ProtocolPaused);
+        require!(!protocol.slashing_frozen, CovenantError::SlashingFrozen);
+        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(provider.stake_amount > 0, CovenantError::NoStakeToSlash);
+
+        // Calculate the settled slash amount
+        let now = Clock::get()?.unix_timestamp;
         let slash_amount = (provider.stake_amount as u128)
-            .checked_mul(sla.penalty_percentage as u128)
+            .checked_mul(sla.effective_penalty(now) as u128)
+            .unwrap()
+            .checked_mul(protocol.settlement_discount_bps as u128)
             .unwrap()
-            .checked_div(100)
+            .checked_div(100 * 10_000)
             .unwrap() as u64;
+        let slash_amount = scale_by_duration(slash_amount, violation.duration_seconds(), protocol);
 
-        let actual_slash = std::cmp::min(slash_amount, provider.stake_amount);
+        let acknowledged_mask = if acknowledged_claims.is_empty() {
+            violation.all_claims_mask() & !violation.settled_claims
+        } else {
+            violation.claims_mask(&acknowledged_claims)?
+        };
+        let slash_amount =
+            violation.claims_share(slash_amount, acknowledged_mask.count_ones() as usize);
+        violation.settled_claims |= acknowledged_mask;
 
-        // Transfer slashed amount from vault to reporter (compensation)
-        let protocol_seeds = &[
-            b"protocol".as_ref(),
-            &[protocol.bump],
-        ];
-        let signer_seeds = &[&protocol_seeds[..]];
+        let actual_slash = execute_slash(
+            protocol,
+            provider,
+            ctx.accounts.parent_provider.as_mut(),
+            violation,
+            slash_amount,
+            &ctx.accounts.system_program,
+            &ctx.accounts.stake_vault,
+            ctx.bumps.stake_vault,
+            ctx.accounts.reporter.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.honest_pool.to_account_info(),
+        )?;
+        if violation.is_resolved {
+            ctx.accounts.reporter_record.confirmed_reports += 1;
+        }
 
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.stake_vault.to_account_info(),
-                to: ctx.accounts.reporter.to_account_info(),
-            },
-            signer_seeds,
+        emit!(ViolationSettled {
+            violation: violation.key(),
+            provider: provider.key(),
+            reporter: violation.reporter,
+            amount: actual_slash,
+        });
+        msg!("Violation settled for {} lamports", actual_slash);
+        Ok(())
+    }
+
+    /// Dismiss an unresolved violation whose evidence has expired, without slashing (permissionless).
+    /// The violation account is closed and its rent returned to whoever paid it (the reporter,
+    /// its rent sponsor, or the provider whose allowance paid it), and the caller may earn
+    /// the protocol's crank reward.
+    pub fn expire_violation(ctx: Context<ExpireViolation>) -> Result<()> {
+        let violation = &ctx.accounts.violation;
+        let provider = &mut ctx.accounts.provider;
+
+        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(
+            Clock::get()?.unix_timestamp > violation.evidence_valid_until,
+            CovenantError::EvidenceStillValid
         );
-        system_program::transfer(cpi_context, actual_slash)?;
 
-        // Update state
-        provider.stake_amount -= actual_slash;
-        protocol.total_slashed += actual_slash;
-        protocol.total_staked -= actual_slash;
-        violation.is_resolved = true;
+        provider.pending_violations -= 1;
+        provider.dismissed_violations += 1;
+        provider.forget_violation(violation.violation_type, violation.timestamp, &ctx.accounts.protocol)?;
+        ctx.accounts.reporter_record.dismissed_reports += 1;
 
-        // Deactivate provider if stake falls below minimum
-        if provider.stake_amount < MIN_STAKE {
-            provider.is_active = false;
-            msg!("Provider deactivated due to insufficient stake");
+        emit!(ViolationExpired {
+            violation: violation.key(),
+            provider: provider.key(),
+            reporter: violation.reporter,
+        });
+
+        let cranker_state = &mut ctx.accounts.cranker_state;
+        cranker_state.cranker = ctx.accounts.cranker.key();
+        cranker_state.bump = ctx.bumps.cranker_state;
+        pay_crank_reward(
+            &mut ctx.accounts.protocol,
+            cranker_state,
+            &ctx.accounts.treasury,
+            ctx.bumps.treasury,
+            ctx.accounts.cranker.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        msg!("Violation expired without slashing");
+        Ok(())
+    }
+
+    /// Dismiss an unresolved violation on review, without slashing (authority only). The
+    /// violation is closed with its rent returned to whoever paid it, and
+    /// `dismissal_refund_bps` of the report fee is refunded from the treasury to whoever
+    /// paid the fee. A report flagged `malicious` forfeits the whole fee.
+    pub fn dismiss_violation(ctx: Context<DismissViolation>, malicious: bool) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let violation = &ctx.accounts.violation;
+        let provider = &mut ctx.accounts.provider;
+        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+
+        provider.pending_violations -= 1;
+        provider.dismissed_violations += 1;
+        provider.forget_violation(violation.violation_type, violation.timestamp, &ctx.accounts.protocol)?;
+        ctx.accounts.reporter_record.dismissed_reports += 1;
+
+        let (refunded, forfeited) =
+            dismissal_refund(violation.report_fee, ctx.accounts.protocol.dismissal_refund_bps, malicious);
+        transfer_from_treasury(
+            &ctx.accounts.system_program,
+            &ctx.accounts.treasury,
+            ctx.bumps.treasury,
+            ctx.accounts.fee_payer.to_account_info(),
+            refunded,
+        )?;
+
+        emit!(ViolationDismissed {
+            violation: violation.key(),
+            provider: provider.key(),
+            reporter: violation.reporter,
+            malicious,
+            refunded,
+            forfeited,
+        });
+        msg!("Violation dismissed, {} lamports of the report fee refunded", refunded);
+        Ok(())
+    }
+
+    /// Submit evidence of remediation while the violation's cure window is open. Until the
+    /// cure is reviewed the violation can't be slashed.
+    pub fn cure_violation(ctx: Context<CureViolation>, remediation_hash: [u8; 32]) -> Result<()> {
+        let violation = &mut ctx.accounts.violation;
+
+        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(!violation.cure_submitted, CovenantError::CurePending);
+        require!(
+            Clock::get()?.unix_timestamp < violation.cure_deadline,
+            CovenantError::CureWindowClosed
+        );
+
+        violation.cure_submitted = true;
+        violation.cure_evidence_hash = remediation_hash;
+
+        emit!(CureSubmitted {
+            violation: violation.key(),
+            provider: violation.provider,
+            remediation_hash,
+        });
+        msg!("Cure submitted for review");
+        Ok(())
+    }
+
+    /// Accept or reject a submitted cure (authority only). An accepted cure resolves the
+    /// violation without slashing, though it still counts as confirmed on the provider's
+    /// record; a rejected cure leaves the violation open to slashing and counts as a lost
+    /// dispute, which shortens the provider's future cure windows once past the threshold.
+    pub fn review_cure(ctx: Context<ReviewCure>, accept: bool) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let violation = &mut ctx.accounts.violation;
+        require!(!violation.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(violation.cure_submitted, CovenantError::NoCureSubmitted);
+
+        violation.cure_submitted = false;
+        if accept {
+            violation.is_cured = true;
+            violation.is_resolved = true;
+            violation.settled_claims = violation.all_claims_mask();
+
+            let provider = &mut ctx.accounts.provider;
+            provider.pending_violations -= 1;
+            provider.confirmed_violations += 1;
+            ctx.accounts.reporter_record.confirmed_reports += 1;
+        } else {
+            ctx.accounts.provider.disputes_lost += 1;
         }
 
-        msg!("Slashed {} lamports from provider", actual_slash);
+        emit!(CureReviewed {
+            violation: violation.key(),
+            provider: violation.provider,
+            accepted: accept,
+        });
+        msg!("Cure {}", if accept { "accepted" } else { "rejected" });
+        Ok(())
+    }
+
+    /// File a consumer complaint against a provider. Complaints never trigger slashing,
+    /// but unresolved ones weigh on reputation until the provider resolves them.
+    pub fn file_complaint(
+        ctx: Context<FileComplaint>,
+        complaint_type: ComplaintType,
+        description_hash: [u8; 32],     // Hash of the off-chain grievance
+    ) -> Result<()> {
+        require!(ctx.accounts.provider.is_serving(), CovenantError::ProviderInactive);
+
+        let complaint = &mut ctx.accounts.complaint;
+        complaint.provider = ctx.accounts.provider.key();
+        complaint.consumer = ctx.accounts.consumer.key();
+        complaint.complaint_type = complaint_type;
+        complaint.description_hash = description_hash;
+        complaint.timestamp = Clock::get()?.unix_timestamp;
+        complaint.is_resolved = false;
+        complaint.bump = ctx.bumps.complaint;
+
+        let provider = &mut ctx.accounts.provider;
+        provider.complaints += 1;
+        provider.open_complaints += 1;
+        provider.refresh_reputation(&ctx.accounts.protocol)?;
+
+        emit!(ComplaintFiled {
+            complaint: complaint.key(),
+            provider: provider.key(),
+            consumer: complaint.consumer,
+            complaint_type,
+        });
+        msg!("Complaint filed against provider");
+        Ok(())
+    }
+
+    /// Mark a complaint as resolved (provider authority), lifting its reputation penalty
+    pub fn resolve_complaint(ctx: Context<ResolveComplaint>) -> Result<()> {
+        let complaint = &mut ctx.accounts.complaint;
+        require!(!complaint.is_resolved, CovenantError::ComplaintAlreadyResolved);
+        complaint.is_resolved = true;
+
+        let provider = &mut ctx.accounts.provider;
+        provider.open_complaints -= 1;
+        provider.refresh_reputation(&ctx.accounts.protocol)?;
+
+        emit!(ComplaintResolved {
+            complaint: complaint.key(),
+            provider: provider.key(),
+        });
+        msg!("Complaint resolved");
         Ok(())
     }
 
     /// Record a successful service request (builds reputation)
-    pub fn record_success(ctx: Context<RecordSuccess>) -> Result<()> {
+    ///
+    /// If a `request_id` is given and matches one of the provider's last
+    /// `RECENT_REQUEST_IDS` ids, the call is a silent no-op so clients can
+    /// safely retry.
+    pub fn record_success(ctx: Context<RecordSuccess>, request_id: Option<[u8; 16]>) -> Result<()> {
         let provider = &mut ctx.accounts.provider;
+        require!(provider.status != ProviderStatus::Banned, CovenantError::ProviderBlacklisted);
+        require!(
+            provider.status != ProviderStatus::PendingApproval,
+            CovenantError::ProviderPendingApproval
+        );
+
+        if let Some(request_id) = request_id {
+            require!(request_id != [0; 16], CovenantError::InvalidRequestId);
+            if provider.recent_request_ids.contains(&request_id) {
+                msg!("Duplicate request id, success already recorded");
+                return Ok(());
+            }
+            let cursor = provider.recent_request_cursor as usize;
+            provider.recent_request_ids[cursor] = request_id;
+            provider.recent_request_cursor = ((cursor + 1) % RECENT_REQUEST_IDS) as u8;
+        }
+
+        // Providers whose reputation fell below the floor can only rebuild while on probation,
+        // unless too few distinct reporters are behind their violations for the floor to apply
+        let protocol = &ctx.accounts.protocol;
+        require!(
+            provider.violations == 0
+                || provider.distinct_reporter_count < protocol.min_distinct_reporters
+                || provider.status == ProviderStatus::Probation
+                || provider.reputation_score >= protocol.min_reputation_to_serve,
+            CovenantError::ReputationTooLow
+        );
+
+        provider.sync_season(protocol);
         provider.successful_requests += 1;
+        provider.season_successes += 1;
+        provider.refresh_reputation(protocol)?;
+
+        if provider.status == ProviderStatus::Probation
+            && provider.reputation_score >= protocol.min_reputation_to_serve
+        {
+            provider.status = ProviderStatus::Active;
+            emit!(ProbationEnded { provider: provider.key() });
+            msg!("Provider completed probation");
+        }
 
         msg!("Successful request recorded. Total: {}", provider.successful_requests);
         Ok(())
     }
 
+    /// Publish the provider's own uptime, latency and accuracy figures for a past period.
+    /// A later violation inside the period that contradicts these claims is slashed harder.
+    pub fn self_attest(
+        ctx: Context<SelfAttest>,
+        period_start: i64,
+        period_end: i64,
+        uptime_percentage: u8,          // Claimed uptime (0-100)
+        response_time_ms: u32,          // Claimed worst-case response time
+        accuracy_percentage: u8,        // Claimed accuracy (0-100)
+    ) -> Result<()> {
+        require!(ctx.accounts.provider.is_serving(), CovenantError::ProviderInactive);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            period_start < period_end && period_end <= now,
+            CovenantError::InvalidAttestationPeriod
+        );
+        require!(
+            uptime_percentage <= 100 && accuracy_percentage <= 100,
+            CovenantError::InvalidPercentage
+        );
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.provider = ctx.accounts.provider.key();
+        attestation.period_start = period_start;
+        attestation.period_end = period_end;
+        attestation.uptime_percentage = uptime_percentage;
+        attestation.response_time_ms = response_time_ms;
+        attestation.accuracy_percentage = accuracy_percentage;
+        attestation.timestamp = now;
+        attestation.bump = ctx.bumps.attestation;
+        ctx.accounts.provider.attestations += 1;
+
+        emit!(SelfAttested {
+            attestation: attestation.key(),
+            provider: attestation.provider,
+            period_start,
+            period_end,
+        });
+        msg!("Self-attestation recorded");
+        Ok(())
+    }
+
+    /// Recompute a provider's cached reputation from its source counters (permissionless)
+    pub fn refresh_provider_metrics(ctx: Context<RefreshProviderMetrics>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        provider.refresh_reputation(&ctx.accounts.protocol)?;
+
+        emit!(ProviderMetricsRefreshed {
+            provider: provider.key(),
+            reputation_score: provider.reputation_score,
+            overcollateralization_bonus_bps: provider.overcollateralization_bonus_bps,
+        });
+        msg!("Provider metrics refreshed. Reputation: {}", provider.reputation_score);
+        Ok(())
+    }
+
+    /// Compare a provider's recorded stake with its vault balance net of the rent reserve
+    /// (permissionless). Emits `VaultDiscrepancy` on a mismatch; with `reconcile`, the
+    /// authority can reset the recorded stake to what the vault actually holds.
+    pub fn audit_vault(ctx: Context<AuditVault>, reconcile: bool) -> Result<()> {
+        let vault_balance = ctx
+            .accounts
+            .stake_vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let recorded_stake = ctx.accounts.provider.stake_amount;
+
+        if vault_balance == recorded_stake {
+            msg!("Vault balance matches recorded stake");
+            return Ok(());
+        }
+
+        emit!(VaultDiscrepancy {
+            provider: ctx.accounts.provider.key(),
+            recorded_stake,
+            vault_balance,
+            reconciled: reconcile,
+        });
+
+        if reconcile {
+            ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+            let protocol = &mut ctx.accounts.protocol;
+            let provider = &mut ctx.accounts.provider;
+            protocol.total_staked = protocol
+                .total_staked
+                .saturating_sub(recorded_stake)
+                .checked_add(vault_balance)
+                .ok_or(CovenantError::MathOverflow)?;
+            if recorded_stake == 0 && vault_balance > 0 {
+                protocol.total_providers += 1;
+            } else if recorded_stake > 0 && vault_balance == 0 {
+                protocol.total_providers -= 1;
+            }
+            provider.stake_amount = vault_balance;
+            provider.refresh_reputation(protocol)?;
+            msg!("Recorded stake reconciled to {} lamports", vault_balance);
+        } else {
+            msg!("Vault discrepancy: recorded {} lamports, vault holds {}", recorded_stake, vault_balance);
+        }
+        Ok(())
+    }
+
     /// Withdraw stake (only if no pending violations and cooldown passed)
-    pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
+    ///
+    /// With `close_on_zero`, a withdrawal that drains the stake also closes the
+    /// provider account and refunds its rent to the authority. `min_expected_stake`
+    /// guards against a slash landing between building and submitting the transaction.
+    pub fn withdraw_stake(
+        ctx: Context<WithdrawStake>,
+        amount: u64,
+        close_on_zero: bool,
+        min_expected_stake: Option<u64>,
+    ) -> Result<()> {
         let provider = &mut ctx.accounts.provider;
         let protocol = &mut ctx.accounts.protocol;
 
-        require!(provider.is_active, CovenantError::ProviderInactive);
+        if let Some(min_expected_stake) = min_expected_stake {
+            require!(
+                provider.stake_amount >= min_expected_stake,
+                CovenantError::StakeChangedUnexpectedly
+            );
+        }
+        // A provider deactivated by a slash or banned can still take out what's left once
+        // nothing is pending
+        require!(
+            !matches!(provider.status, ProviderStatus::Inactive | ProviderStatus::Banned)
+                || provider.pending_violations == 0,
+            CovenantError::PendingViolations
+        );
+        require!(!provider.withdrawals_frozen, CovenantError::WithdrawalsFrozen);
         require!(amount <= provider.stake_amount, CovenantError::InsufficientStake);
 
         // Ensure minimum stake maintained if still active
@@ -198,119 +1050,4640 @@ pub mod covenant {
             require!(remaining >= MIN_STAKE, CovenantError::WouldBreachMinStake);
         }
 
-        // Transfer from vault to provider
-        let protocol_seeds = &[
-            b"protocol".as_ref(),
-            &[protocol.bump],
-        ];
-        let signer_seeds = &[&protocol_seeds[..]];
+        // Still-unvested collateral stays in the vault (slashing can still take it)
+        let locked = provider
+            .vesting
+            .locked_at(Clock::get()?.unix_timestamp)
+            .min(provider.stake_amount);
+        require!(remaining >= locked, CovenantError::StakeLocked);
 
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.stake_vault.to_account_info(),
-                to: ctx.accounts.provider_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        system_program::transfer(cpi_context, amount)?;
+        // Lower-reputation providers can only pull a limited amount out per period
+        let now = Clock::get()?.unix_timestamp;
+        if now.saturating_sub(provider.period_start) >= protocol.withdrawal_period_seconds {
+            provider.period_start = now;
+            provider.withdrawn_this_period = 0;
+        }
+        let withdrawn = provider.withdrawn_this_period.saturating_add(amount);
+        let limit = protocol.withdrawal_limit(provider.reputation_score);
+        require!(limit == 0 || withdrawn <= limit, CovenantError::WithdrawalLimitExceeded);
+        provider.withdrawn_this_period = withdrawn;
+
+        // Transfer from vault to provider
+        transfer_from_vault(
+            &ctx.accounts.system_program,
+            &ctx.accounts.stake_vault,
+            ctx.accounts.provider_authority.to_account_info(),
+            &provider.authority,
+            ctx.bumps.stake_vault,
+            amount,
+        )?;
 
         // Update state
         provider.stake_amount -= amount;
         protocol.total_staked -= amount;
+        provider.refresh_reputation(protocol)?;
+        // Stake the provider chose to take out isn't put back by a top-up
+        if amount > 0 {
+            provider.topup_armed = false;
+        }
 
         if provider.stake_amount == 0 {
-            provider.is_active = false;
-            protocol.total_providers -= 1;
+            // A banned provider stays banned after withdrawing what's left
+            if provider.status != ProviderStatus::Banned {
+                provider.status = ProviderStatus::Inactive;
+            }
+            provider.auto_topup_target = 0;
+            // Only the withdrawal that drains the stake takes the provider out of the count
+            if amount > 0 {
+                protocol.total_providers -= 1;
+            }
+
+            if close_on_zero {
+                require!(provider.pending_violations == 0, CovenantError::PendingViolations);
+                require!(provider.org.is_none(), CovenantError::AlreadyInOrganization);
+                require!(provider.parent.is_none(), CovenantError::InvalidParent);
+                require!(provider.sub_provider_count == 0, CovenantError::InvalidParent);
+                // Violation and complaint PDAs are seeded by the provider's counters; closing
+                // would reset them on re-registration and collide with the existing accounts
+                require!(
+                    provider.violations == 0 && provider.complaints == 0,
+                    CovenantError::ProviderHasHistory
+                );
+
+                // Nothing keyed by the provider may outlive it, or a re-registration under the
+                // same authority would inherit it. Self-attestations and sponsorship allowances
+                // are passed as remaining accounts and closed here too.
+                let provider_authority = ctx.accounts.provider_authority.to_account_info();
+                close_provider_records(provider.key(), provider, ctx.remaining_accounts, &provider_authority)?;
+                require!(
+                    provider.attestations == 0 && provider.sponsorships == 0,
+                    CovenantError::ProviderAccountsOpen
+                );
+                close_if_open(&ctx.accounts.sla, &provider_authority)?;
+                close_if_open(&ctx.accounts.maintenance, &provider_authority)?;
+
+                // Return the vault's rent reserve along with the provider account's rent
+                let authority_key = provider.authority;
+                sign_vault_transfer(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.stake_vault,
+                    ctx.accounts.provider_authority.to_account_info(),
+                    &authority_key,
+                    ctx.bumps.stake_vault,
+                    ctx.accounts.stake_vault.lamports(),
+                )?;
+                sign_funding_transfer(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.funding_source,
+                    ctx.accounts.provider_authority.to_account_info(),
+                    &ctx.accounts.provider.key(),
+                    ctx.bumps.funding_source,
+                    ctx.accounts.funding_source.lamports(),
+                )?;
+                ctx.accounts.provider.close(ctx.accounts.provider_authority.to_account_info())?;
+                msg!("Provider account closed");
+            }
         }
 
         msg!("Withdrew {} lamports", amount);
         Ok(())
     }
-}
 
-// Constants
-pub const MIN_STAKE: u64 = 100_000_000; // 0.1 SOL minimum stake
+    /// Return the full lamport cost of registering with `stake_amount` and defining an SLA
+    /// (read-only): the stake plus rent for the provider, vault, SLA and, when names are
+    /// unique, name registry accounts
+    pub fn estimate_onboarding_cost(
+        ctx: Context<EstimateOnboardingCost>,
+        stake_amount: u64,
+    ) -> Result<OnboardingCost> {
+        let rent = Rent::get()?;
+        let provider_rent = rent.minimum_balance(PROVIDER_SPACE);
+        let vault_rent = rent.minimum_balance(0);
+        let sla_rent = rent.minimum_balance(SLA_SPACE);
+        let name_record_rent = if ctx.accounts.protocol.unique_names {
+            rent.minimum_balance(NAME_RECORD_SPACE)
+        } else {
+            0
+        };
+        let total = [stake_amount, provider_rent, vault_rent, sla_rent, name_record_rent]
+            .iter()
+            .try_fold(0u64, |acc, cost| acc.checked_add(*cost))
+            .ok_or(CovenantError::MathOverflow)?;
+        Ok(OnboardingCost {
+            stake_amount,
+            provider_rent,
+            vault_rent,
+            sla_rent,
+            name_record_rent,
+            total,
+        })
+    }
 
-// Account Structures
+    /// Return protocol-wide totals and derived ratios in one read-only call
+    pub fn get_protocol_stats(ctx: Context<GetProtocolStats>) -> Result<ProtocolStats> {
+        let protocol = &ctx.accounts.protocol;
+        Ok(ProtocolStats {
+            total_providers: protocol.total_providers,
+            total_staked: protocol.total_staked,
+            total_slashed: protocol.total_slashed,
+            slashed_rolling: protocol.current_slashed_rolling(Clock::get()?.unix_timestamp),
+            slash_rate_bps: slash_rate_bps(protocol.total_slashed, protocol.total_staked),
+            average_stake: protocol
+                .total_staked
+                .checked_div(protocol.total_providers)
+                .unwrap_or(0),
+            paused: protocol.paused,
+        })
+    }
 
-#[account]
-pub struct Protocol {
-    pub authority: Pubkey,
-    pub total_providers: u64,
-    pub total_staked: u64,
-    pub total_slashed: u64,
-    pub bump: u8,
-}
+    /// Snapshot of one provider's health (read-only): status, profile, stake and reputation,
+    /// violation outcomes, capabilities, whether a maintenance window is open now, current
+    /// season and lifetime counters, and how long its SLA terms are locked (when `sla` is given).
+    pub fn provider_health(ctx: Context<GetProviderHealth>) -> Result<ProviderHealth> {
+        let protocol = &ctx.accounts.protocol;
+        let now = Clock::get()?.unix_timestamp;
+        // Season counters roll over lazily; report them as of the current season
+        let mut provider = (*ctx.accounts.provider).clone();
+        provider.sync_season(protocol);
 
-#[account]
-pub struct Provider {
-    pub authority: Pubkey,
-    pub name: String,
-    pub service_endpoint: String,
-    pub stake_amount: u64,
-    pub violations: u64,
-    pub successful_requests: u64,
-    pub created_at: i64,
-    pub is_active: bool,
-    pub bump: u8,
-}
+        let maintenance = &ctx.accounts.maintenance;
+        let in_maintenance = maintenance.owner == ctx.program_id
+            && !maintenance.data_is_empty()
+            && MaintenanceWindow::try_deserialize(&mut &maintenance.try_borrow_data()?[..])?.is_active_at(now);
 
-#[account]
-pub struct SLA {
-    pub provider: Pubkey,
-    pub uptime_guarantee: u8,
-    pub max_response_time_ms: u32,
-    pub accuracy_guarantee: u8,
-    pub penalty_percentage: u8,
-    pub created_at: i64,
-    pub is_active: bool,
-    pub bump: u8,
-}
+        Ok(ProviderHealth {
+            provider: ctx.accounts.provider.key(),
+            status: provider.status,
+            region: provider.region,
+            endpoint_version: provider.endpoint_version,
+            stake_amount: provider.stake_amount,
+            reputation_score: provider.reputation_score,
+            overcollateralization_bonus_bps: provider.overcollateralization_bonus_bps,
+            pending_violations: provider.pending_violations,
+            confirmed_violations: provider.confirmed_violations,
+            dismissed_violations: provider.dismissed_violations,
+            capabilities: provider.capabilities,
+            in_maintenance,
+            season: provider.season,
+            season_successes: provider.season_successes,
+            season_violation_counts: provider.season_violation_counts,
+            successful_requests: provider.successful_requests,
+            violation_counts: provider.violation_counts,
+            sla_locked_until: ctx.accounts.sla.as_ref().map(|sla| sla.locked_until),
+        })
+    }
 
-#[account]
-pub struct Violation {
-    pub provider: Pubkey,
-    pub reporter: Pubkey,
-    pub violation_type: ViolationType,
+    /// Largest amount a reporter could currently recover from one slash of this provider
+    /// (read-only), priced the way `slash` would charge it and limited by the protected balance,
+    /// single-slash ceiling, minimum slash and reporter compensation cap.
+    ///
+    /// With `violation`, prices that report (zero until it can be slashed) including any
+    /// false-attestation aggravation from `attestation`. Without it, prices a new report at the
+    /// longest-duration scale, plus the first-reporter bonus while a violation type is still
+    /// unseen. Zero while slashing is paused or frozen or the provider is onboarding-shielded.
+    pub fn max_recoverable(ctx: Context<MaxRecoverable>) -> Result<u64> {
+        let protocol = &ctx.accounts.protocol;
+        let provider = &ctx.accounts.provider;
+        let sla = &ctx.accounts.sla;
+        let now = Clock::get()?.unix_timestamp;
+        if protocol.paused || protocol.slashing_frozen || provider.onboarding_shielded(protocol, now) {
+            return Ok(0);
+        }
+
+        let penalty = match &ctx.accounts.violation {
+            Some(violation) => {
+                if violation.require_slashable(now).is_err() {
+                    return Ok(0);
+                }
+                let penalty = violation_penalty(
+                    provider.stake_amount,
+                    sla.effective_penalty(now),
+                    violation.duration_seconds(),
+                    violation.first_of_type,
+                    protocol,
+                );
+                let penalty = match &ctx.accounts.attestation {
+                    Some(attestation) if attestation.contradicted_by(violation, sla) => {
+                        penalty.saturating_add(false_attestation_penalty(penalty, protocol))
+                    }
+                    _ => penalty,
+                };
+                violation.claims_share(penalty, violation.open_claims())
+            }
+            None => violation_penalty(
+                provider.stake_amount,
+                sla.effective_penalty(now),
+                i64::MAX,
+                provider.seen_violation_types != (1u8 << 5) - 1,
+                protocol,
+            ),
+        };
+
+        let slashable = limit_slash(penalty, provider.stake_amount, protocol).unwrap_or(0);
+        Ok(reporter_compensation(slashable, protocol.max_reporter_compensation))
+    }
+
+    /// Share (bps) of a reporter's resolved reports that were confirmed (read-only). Slashed,
+    /// fully acknowledged and cured reports count as confirmed, expired ones as dismissed.
+    pub fn reporter_accuracy(ctx: Context<ReporterAccuracy>) -> Result<u64> {
+        let record = &ctx.accounts.reporter_record;
+        Ok(reporter_accuracy_bps(record.confirmed_reports, record.dismissed_reports))
+    }
+
+    /// Return the protocol, provider, vault and SLA addresses for a provider authority
+    /// (read-only; Anchor hands the result back through the transaction's return data)
+    pub fn derive_addresses(
+        ctx: Context<DeriveAddresses>,
+        provider_authority: Pubkey,
+    ) -> Result<DerivedAddresses> {
+        let (protocol, _) = Pubkey::find_program_address(&[b"protocol"], ctx.program_id);
+        let (provider, _) =
+            Pubkey::find_program_address(&[b"provider", provider_authority.as_ref()], ctx.program_id);
+        let (stake_vault, _) =
+            Pubkey::find_program_address(&[b"vault", provider_authority.as_ref()], ctx.program_id);
+        let (sla, _) = Pubkey::find_program_address(&[b"sla", provider.as_ref()], ctx.program_id);
+
+        Ok(DerivedAddresses {
+            protocol,
+            provider,
+            stake_vault,
+            sla,
+        })
+    }
+
+    /// Summarize the SLAs a provider can be held to (read-only): its own and, for a
+    /// sub-provider, its parent's, passed as remaining accounts. Each must be one of those
+    /// (`SLAMismatch` otherwise); summaries come back in the order the accounts were passed.
+    pub fn get_slas(ctx: Context<GetSlas>) -> Result<Vec<SLASummary>> {
+        let provider = &ctx.accounts.provider;
+        let now = Clock::get()?.unix_timestamp;
+        ctx.remaining_accounts
+            .iter()
+            .map(|account| {
+                require_keys_eq!(*account.owner, crate::ID, CovenantError::SLAMismatch);
+                let sla = SLA::try_deserialize(&mut &account.try_borrow_data()?[..])
+                    .map_err(|_| error!(CovenantError::SLAMismatch))?;
+                require!(
+                    sla.provider == provider.key() || Some(sla.provider) == provider.parent,
+                    CovenantError::SLAMismatch
+                );
+                Ok(SLASummary {
+                    sla: account.key(),
+                    provider: sla.provider,
+                    uptime_guarantee: sla.uptime_guarantee,
+                    max_response_time_ms: sla.max_response_time_ms,
+                    accuracy_guarantee: sla.accuracy_guarantee,
+                    penalty_percentage: sla.effective_penalty(now),
+                    is_active: sla.is_active,
+                    locked_until: sla.locked_until,
+                })
+            })
+            .collect()
+    }
+
+    /// Set how far above the minimum stake (in basis points) a slash triggers a StakeLow warning
+    pub fn set_stake_warning_threshold(ctx: Context<UpdateConfig>, threshold_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.stake_warning_threshold_bps = threshold_bps;
+
+        msg!("Stake warning threshold set to {} bps", threshold_bps);
+        Ok(())
+    }
+
+    /// Set the reputation penalty weight for each violation type (indexed by ViolationType)
+    pub fn set_violation_weights(ctx: Context<UpdateConfig>, weights: [u16; 5]) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.violation_weights = weights;
+
+        msg!("Violation weights set to {:?}", weights);
+        Ok(())
+    }
+
+    /// Set the maximum reputation bonus (in basis points) for stake held above the minimum
+    pub fn set_overcollateralization_bonus_cap(ctx: Context<UpdateConfig>, cap_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.overcollateralization_bonus_cap_bps = cap_bps;
+
+        msg!("Overcollateralization bonus cap set to {} bps", cap_bps);
+        Ok(())
+    }
+
+    /// Set the fraction of the SLA penalty (in basis points) charged when a provider acknowledges a violation
+    pub fn set_settlement_discount(ctx: Context<UpdateConfig>, settlement_discount_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(settlement_discount_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.settlement_discount_bps = settlement_discount_bps;
+
+        msg!("Settlement discount set to {} bps", settlement_discount_bps);
+        Ok(())
+    }
+
+    /// Set the reputation a provider with violations needs to keep recording successes
+    pub fn set_min_reputation_to_serve(ctx: Context<UpdateConfig>, min_reputation: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.min_reputation_to_serve = min_reputation;
+
+        msg!("Minimum reputation to serve set to {}", min_reputation);
+        Ok(())
+    }
+
+    /// Set how many distinct reporter keys must have filed against a provider before the
+    /// reputation floor can stop it recording successes (0 applies the floor regardless)
+    pub fn set_min_distinct_reporters(ctx: Context<UpdateConfig>, min_reporters: u8) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(
+            min_reporters as usize <= MAX_TRACKED_REPORTERS,
+            CovenantError::TooManyDistinctReporters
+        );
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.min_distinct_reporters = min_reporters;
+
+        msg!("Reputation floor applies after {} distinct reporters", min_reporters);
+        Ok(())
+    }
+
+    /// Set how long violation evidence stays actionable (0 disables expiry)
+    pub fn set_evidence_ttl(ctx: Context<UpdateConfig>, ttl_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(ttl_seconds >= 0, CovenantError::InvalidDuration);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.evidence_ttl_seconds = ttl_seconds;
+
+        msg!("Evidence TTL set to {} seconds", ttl_seconds);
+        Ok(())
+    }
+
+    /// Set the stake balance that slashing never takes a provider below
+    pub fn set_protected_balance(ctx: Context<UpdateConfig>, protected_balance: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.protected_balance = protected_balance;
+
+        msg!("Protected balance set to {} lamports", protected_balance);
+        Ok(())
+    }
+
+    /// Set how long after registration a provider can be reported but not slashed (0 disables)
+    pub fn set_onboarding_shield(ctx: Context<UpdateConfig>, shield_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(shield_seconds >= 0, CovenantError::InvalidDuration);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.onboarding_shield_seconds = shield_seconds;
+
+        msg!("Onboarding shield set to {} seconds", shield_seconds);
+        Ok(())
+    }
+
+    /// Set the maximum violation description length (violation accounts are sized for MAX_DESCRIPTION_LEN)
+    pub fn set_max_description_len(ctx: Context<UpdateConfig>, max_len: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(max_len as usize <= MAX_DESCRIPTION_LEN, CovenantError::DescriptionTooLong);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.max_description_len = max_len;
+
+        msg!("Max description length set to {}", max_len);
+        Ok(())
+    }
+
+    /// Set the extra slash (in basis points of the penalty) paid to the first reporter of a
+    /// violation type not previously recorded against the provider
+    pub fn set_first_reporter_bonus(ctx: Context<UpdateConfig>, bonus_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(bonus_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.first_reporter_bonus_bps = bonus_bps;
+
+        msg!("First-reporter bonus set to {} bps", bonus_bps);
+        Ok(())
+    }
+
+    /// Turn the report_violation self-dealing checks on or off
+    pub fn set_self_dealing_checks(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.self_dealing_checks = enabled;
+
+        msg!("Self-dealing checks {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Require display names to be unique (case-insensitive) for new registrations
+    pub fn set_unique_names(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.unique_names = enabled;
+
+        msg!("Unique provider names {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Give up a claimed display name (its holder), refunding the registry account's rent.
+    /// Only possible once the provider that claimed it has been closed, so a live provider
+    /// never loses its name to someone else.
+    pub fn release_name(ctx: Context<ReleaseName>, _name: String) -> Result<()> {
+        emit!(NameReleased {
+            authority: ctx.accounts.authority.key(),
+            provider: ctx.accounts.name_record.provider,
+        });
+        msg!("Provider name released");
+        Ok(())
+    }
+
+    /// Set how long a violation keeps weighing on reputation, fading out over the window (0 disables)
+    pub fn set_violation_decay(ctx: Context<UpdateConfig>, decay_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(decay_seconds >= 0, CovenantError::InvalidDuration);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.violation_decay_seconds = decay_seconds;
+
+        msg!("Violation decay set to {} seconds", decay_seconds);
+        Ok(())
+    }
+
+    /// Set how many successful requests a provider needs before it can define an SLA
+    pub fn set_min_successes_for_sla(ctx: Context<UpdateConfig>, min_successes: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.min_successes_for_sla = min_successes;
+
+        msg!("Minimum successes for an SLA set to {}", min_successes);
+        Ok(())
+    }
+
+    /// Scale penalties by breach duration: `bps_per_hour` of the SLA penalty per started hour,
+    /// up to `cap_bps` (0 per hour restores the flat penalty)
+    pub fn set_duration_penalty(ctx: Context<UpdateConfig>, bps_per_hour: u16, cap_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(cap_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.duration_penalty_bps_per_hour = bps_per_hour;
+        protocol.max_duration_penalty_bps = cap_bps;
+
+        msg!("Duration penalty set to {} bps/hour, capped at {} bps", bps_per_hour, cap_bps);
+        Ok(())
+    }
+
+    /// Cap the reporter's compensation from any single slash; the excess goes to the treasury (0 removes the cap)
+    pub fn set_max_reporter_compensation(ctx: Context<UpdateConfig>, max_compensation: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.max_reporter_compensation = max_compensation;
+
+        msg!("Reporter compensation capped at {} lamports", max_compensation);
+        Ok(())
+    }
+
+    /// Set the minimum time (seconds) between changes to a provider's SLA terms (0 disables)
+    pub fn set_min_sla_update_interval(ctx: Context<UpdateConfig>, interval_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(interval_seconds >= 0, CovenantError::InvalidDuration);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.min_sla_update_interval = interval_seconds;
+
+        msg!("Minimum SLA update interval set to {} seconds", interval_seconds);
+        Ok(())
+    }
+
+    /// Cap any single slash at this share (bps) of the provider's current stake
+    pub fn set_max_single_slash(ctx: Context<UpdateConfig>, max_single_slash_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(
+            max_single_slash_bps > 0 && max_single_slash_bps <= 10_000,
+            CovenantError::InvalidBasisPoints
+        );
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.max_single_slash_bps = max_single_slash_bps;
+
+        msg!("Single slash capped at {} bps of stake", max_single_slash_bps);
+        Ok(())
+    }
+
+    /// Set the smallest slash worth executing (0 disables the check)
+    pub fn set_min_slash_amount(ctx: Context<UpdateConfig>, min_slash_amount: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.min_slash_amount = min_slash_amount;
+
+        msg!("Minimum slash amount set to {} lamports", min_slash_amount);
+        Ok(())
+    }
+
+    /// Set the extra penalty (bps of the slash) when a violation contradicts a self-attestation
+    pub fn set_false_attestation_penalty(ctx: Context<UpdateConfig>, penalty_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(penalty_bps <= 10_000, CovenantError::InvalidBasisPoints);
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.false_attestation_penalty_bps = penalty_bps;
+
+        msg!("False attestation penalty set to {} bps", penalty_bps);
+        Ok(())
+    }
+
+    /// Set reputation-tiered withdrawal limits: a provider whose reputation reaches
+    /// `min_reputations[i]` (the highest such tier) may withdraw up to `max_withdrawal_per_period[i]`
+    /// lamports per `period_seconds`; below every tier the first tier's limit applies.
+    /// A limit of 0 means unlimited.
+    pub fn set_withdrawal_limits(
+        ctx: Context<UpdateConfig>,
+        min_reputations: [u64; WITHDRAWAL_TIERS],
+        max_withdrawal_per_period: [u64; WITHDRAWAL_TIERS],
+        period_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(
+            min_reputations.windows(2).all(|pair| pair[0] <= pair[1]),
+            CovenantError::InvalidWithdrawalTiers
+        );
+        require!(period_seconds > 0, CovenantError::InvalidWithdrawalTiers);
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.withdrawal_tier_reputations = min_reputations;
+        protocol.max_withdrawal_per_period = max_withdrawal_per_period;
+        protocol.withdrawal_period_seconds = period_seconds;
+
+        msg!("Withdrawal limits set to {:?} per {} seconds", max_withdrawal_per_period, period_seconds);
+        Ok(())
+    }
+
+    /// Set how long (seconds) a provider has after a report to submit a cure; 0 disables cures
+    pub fn set_cure_window(ctx: Context<UpdateConfig>, cure_window_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(cure_window_seconds >= 0, CovenantError::InvalidCureWindow);
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.cure_window_seconds = cure_window_seconds;
+
+        msg!("Cure window set to {} seconds", cure_window_seconds);
+        Ok(())
+    }
+
+    /// Give one violation type its own cure window, e.g. longer for accuracy disputes that
+    /// take days to gather counter-evidence; 0 falls back to `cure_window_seconds`
+    pub fn set_cure_window_for_type(
+        ctx: Context<UpdateConfig>,
+        violation_type: ViolationType,
+        cure_window_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(cure_window_seconds >= 0, CovenantError::InvalidCureWindow);
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.cure_window_by_type[violation_type as usize] = cure_window_seconds;
+
+        msg!("Cure window for violation type {} set to {} seconds", violation_type as u8, cure_window_seconds);
+        Ok(())
+    }
+
+    /// Number of rejected cures after which a provider's cure windows start halving with
+    /// each further rejection; 0 disables escalation
+    pub fn set_cure_escalation(ctx: Context<UpdateConfig>, threshold: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.cure_escalation_threshold = threshold;
+
+        msg!("Cure windows escalate after {} lost disputes", threshold);
+        Ok(())
+    }
+
+    /// Set the fee (lamports) charged to reporters and paid into the treasury, up to
+    /// `MAX_REPORT_FEE` (authority or config manager; the cap bounds what either can charge)
+    pub fn set_report_fee(ctx: Context<UpdateConfig>, report_fee: u64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(report_fee <= MAX_REPORT_FEE, CovenantError::ReportFeeTooHigh);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.report_fee = report_fee;
+
+        msg!("Report fee set to {} lamports", report_fee);
+        Ok(())
+    }
+
+    /// Set the share (bps) of the report fee refunded when a violation is dismissed in good
+    /// faith; malicious reports always forfeit the whole fee
+    pub fn set_dismissal_refund(ctx: Context<UpdateConfig>, refund_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(refund_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.dismissal_refund_bps = refund_bps;
+
+        msg!("Dismissal refund set to {} bps of the report fee", refund_bps);
+        Ok(())
+    }
+
+    /// Set which violation types (indexed by ViolationType) must be reported with an evidence URI
+    pub fn set_evidence_requirements(ctx: Context<UpdateConfig>, uri_required: [bool; 5]) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.evidence_uri_required = uri_required;
+
+        msg!("Evidence requirements updated");
+        Ok(())
+    }
+
+    /// Set the slash volume per CIRCUIT_BREAKER_WINDOW_SECONDS that pauses the protocol (0 disables)
+    pub fn set_circuit_breaker_threshold(ctx: Context<UpdateConfig>, threshold: u64) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.circuit_breaker_threshold = threshold;
+
+        msg!("Circuit breaker threshold set to {} lamports", threshold);
+        Ok(())
+    }
+
+    /// Set how often recent slashing decays by half in the rolling metric
+    pub fn set_rolling_window(ctx: Context<UpdateConfig>, window_seconds: i64) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(window_seconds > 0, CovenantError::InvalidRollingWindow);
+        let now = Clock::get()?.unix_timestamp;
+        let protocol = &mut ctx.accounts.protocol;
+        // Settle decay under the old window before switching
+        protocol.record_rolling_slash(0, now);
+        protocol.rolling_window_seconds = window_seconds;
+
+        msg!("Rolling slash window set to {} seconds", window_seconds);
+        Ok(())
+    }
+
+    /// Delegate routine parameter changes to a config manager (Pubkey::default() removes it).
+    /// Authority transfer, pausing, blacklisting and safety switches stay authority-only.
+    pub fn set_config_manager(ctx: Context<UpdateConfig>, config_manager: Pubkey) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.config_manager = config_manager;
+
+        msg!("Config manager set to {}", config_manager);
+        Ok(())
+    }
+
+    /// Move protocol administration to an N-of-M multisig (or back to the single
+    /// `authority` with an empty set and zero threshold). Calling this from
+    /// single-authority mode is the migration path.
+    pub fn set_multisig(ctx: Context<UpdateConfig>, authorities: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(authorities.len() <= MAX_AUTHORITIES, CovenantError::InvalidMultisig);
+        require!((threshold as usize) <= authorities.len(), CovenantError::InvalidMultisig);
+        require!((threshold == 0) == authorities.is_empty(), CovenantError::InvalidMultisig);
+        for (i, key) in authorities.iter().enumerate() {
+            require!(!authorities[..i].contains(key), CovenantError::InvalidMultisig);
+        }
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.authorities = authorities;
+        protocol.threshold = threshold;
+
+        emit!(MultisigUpdated {
+            authorities: protocol.authorities.clone(),
+            threshold,
+        });
+        msg!("Protocol multisig set to {}-of-{}", threshold, protocol.authorities.len());
+        Ok(())
+    }
+
+    /// Recreate a provider from another deployment's records (authority only). The provider
+    /// starts inactive with no stake and goes live (and joins the provider count) once its
+    /// funding PDA tops it up.
+    pub fn import_provider_snapshot(
+        ctx: Context<ImportProviderSnapshot>,
+        provider_authority: Pubkey,
+        snapshot: ProviderSnapshot,
+    ) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(snapshot.name.len() <= 64, CovenantError::NameTooLong);
+        require!(snapshot.service_endpoint.len() <= 256, CovenantError::EndpointTooLong);
+        require!(is_valid_region(&snapshot.region), CovenantError::InvalidRegion);
+        require!(
+            snapshot
+                .violation_counts
+                .iter()
+                .try_fold(0u64, |sum, count| sum.checked_add(*count))
+                == Some(snapshot.violations),
+            CovenantError::InvalidSnapshot
+        );
+
+        // An imported name is claimed for the provider's authority like a registered one
+        if ctx.accounts.protocol.unique_names {
+            let name_record = ctx
+                .accounts
+                .name_record
+                .as_ref()
+                .ok_or(CovenantError::NameRecordRequired)?;
+            claim_name(
+                name_record,
+                ctx.bumps.name_record.ok_or(CovenantError::NameRecordRequired)?,
+                &snapshot.name,
+                &ctx.accounts.authority,
+                provider_authority,
+                ctx.accounts.provider.key(),
+                &ctx.accounts.system_program,
+            )?;
+        }
+
+        // Fields not carried over keep the zeroed defaults of a fresh account
+        let provider = &mut ctx.accounts.provider;
+        provider.authority = provider_authority;
+        provider.name = snapshot.name;
+        provider.service_endpoint = snapshot.service_endpoint;
+        provider.endpoint_version = snapshot.endpoint_version;
+        provider.region = snapshot.region;
+        provider.created_at = snapshot.created_at;
+        provider.successful_requests = snapshot.successful_requests;
+        provider.violations = snapshot.violations;
+        provider.violation_counts = snapshot.violation_counts;
+        provider.season = ctx.accounts.protocol.season;
+        provider.season_successes = snapshot.successful_requests;
+        provider.season_violation_counts = snapshot.violation_counts;
+        provider.confirmed_violations = snapshot.violations;
+        provider.last_violation_at = snapshot.last_violation_at;
+        provider.seen_violation_types = snapshot
+            .violation_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .fold(0u8, |mask, (i, _)| mask | 1 << i);
+        provider.status = ProviderStatus::Inactive;
+        provider.bump = ctx.bumps.provider;
+        provider.imported = true;
+        // An import holds no stake until its funding source tops it up
+        provider.topup_armed = true;
+        provider.refresh_reputation(&ctx.accounts.protocol)?;
+
+        // The vault holds no stake yet, but needs its rent-exempt reserve before it can take any
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.stake_vault.lamports());
+        if rent_shortfall > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, rent_shortfall)?;
+        }
+
+        emit!(ProviderImported {
+            provider: provider.key(),
+            authority: provider_authority,
+            successful_requests: provider.successful_requests,
+            violations: provider.violations,
+        });
+        msg!("Provider imported from snapshot");
+        Ok(())
+    }
+
+    /// Permanently exclude a provider (authority only). Remaining stake can still be withdrawn.
+    pub fn blacklist_provider(ctx: Context<SetBlacklist>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        if provider.status != ProviderStatus::Banned {
+            provider.pre_ban_status = provider.status;
+        }
+        provider.status = ProviderStatus::Banned;
+
+        emit!(ProviderBlacklisted {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider blacklisted");
+        Ok(())
+    }
+
+    /// Lift a provider's blacklisting (authority only). It returns to the status it had
+    /// when banned, or to inactive if its remaining stake has since fallen below the minimum.
+    pub fn unblacklist_provider(ctx: Context<SetBlacklist>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        require!(provider.status == ProviderStatus::Banned, CovenantError::ProviderNotBanned);
+        provider.status = match provider.pre_ban_status {
+            ProviderStatus::PendingApproval => ProviderStatus::PendingApproval,
+            _ if provider.stake_amount < MIN_STAKE => ProviderStatus::Inactive,
+            status => status,
+        };
+
+        emit!(ProviderUnblacklisted {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider removed from blacklist");
+        Ok(())
+    }
+
+    /// Choose between open registration and authority-approved onboarding (authority only)
+    pub fn set_onboarding_mode(ctx: Context<UpdateConfig>, mode: OnboardingMode) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.protocol.onboarding_mode = mode;
+
+        msg!("Onboarding mode updated");
+        Ok(())
+    }
+
+    /// Activate a provider registered while onboarding required approval (authority only)
+    pub fn approve_provider(ctx: Context<ApproveProvider>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        require!(
+            provider.status == ProviderStatus::PendingApproval,
+            CovenantError::ProviderNotPendingApproval
+        );
+        provider.status = ProviderStatus::Active;
+
+        emit!(ProviderApproved {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider approved");
+        Ok(())
+    }
+
+    /// Turn down a pending provider (authority only): its stake and the vault's rent reserve
+    /// are refunded and the provider account is closed
+    pub fn reject_provider(ctx: Context<RejectProvider>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &ctx.accounts.provider;
+        require!(
+            provider.status == ProviderStatus::PendingApproval,
+            CovenantError::ProviderNotPendingApproval
+        );
+        require!(provider.org.is_none(), CovenantError::AlreadyInOrganization);
+        // Closing either side of a hierarchy would leave the other pointing at a closed account
+        require!(provider.parent.is_none(), CovenantError::InvalidParent);
+        require!(provider.sub_provider_count == 0, CovenantError::InvalidParent);
+
+        let stake_amount = provider.stake_amount;
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.total_staked -= stake_amount;
+        protocol.total_providers -= 1;
+
+        sign_vault_transfer(
+            &ctx.accounts.system_program,
+            &ctx.accounts.stake_vault,
+            ctx.accounts.provider_authority.to_account_info(),
+            &ctx.accounts.provider_authority.key(),
+            ctx.bumps.stake_vault,
+            ctx.accounts.stake_vault.lamports(),
+        )?;
+        sign_funding_transfer(
+            &ctx.accounts.system_program,
+            &ctx.accounts.funding_source,
+            ctx.accounts.provider_authority.to_account_info(),
+            &ctx.accounts.provider.key(),
+            ctx.bumps.funding_source,
+            ctx.accounts.funding_source.lamports(),
+        )?;
+
+        emit!(ProviderRejected {
+            provider: ctx.accounts.provider.key(),
+            refunded: stake_amount,
+        });
+        msg!("Provider rejected, {} lamports refunded", stake_amount);
+        Ok(())
+    }
+
+    /// Put an active provider below the reputation floor on probation so it can rebuild by
+    /// recording successes. Probation ends automatically once it clears the floor.
+    pub fn grant_probation(ctx: Context<GrantProbation>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        require!(provider.status != ProviderStatus::Banned, CovenantError::ProviderBlacklisted);
+        require!(provider.status == ProviderStatus::Active, CovenantError::ProviderInactive);
+        require!(
+            provider.reputation_score < ctx.accounts.protocol.min_reputation_to_serve,
+            CovenantError::ReputationAboveFloor
+        );
+        provider.status = ProviderStatus::Probation;
+
+        emit!(ProbationGranted {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider placed on probation");
+        Ok(())
+    }
+
+    /// Freeze a provider's withdrawals while a violation is under investigation (authority only)
+    pub fn freeze_withdrawals(ctx: Context<SetWithdrawalFreeze>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        provider.withdrawals_frozen = true;
+
+        emit!(ProviderWithdrawalsFrozen {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider withdrawals frozen");
+        Ok(())
+    }
+
+    /// Unfreeze a provider's withdrawals (authority only). Freezes are never lifted automatically.
+    pub fn unfreeze_withdrawals(ctx: Context<SetWithdrawalFreeze>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let provider = &mut ctx.accounts.provider;
+        provider.withdrawals_frozen = false;
+
+        emit!(ProviderWithdrawalsUnfrozen {
+            provider: provider.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        msg!("Provider withdrawals unfrozen");
+        Ok(())
+    }
+
+    /// Start a new reputation season (authority only). Providers carry over
+    /// `season_carryover_bps` of their season counters lazily, on their next interaction.
+    pub fn advance_season(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.season = protocol.season.checked_add(1).ok_or(CovenantError::MathOverflow)?;
+        protocol.season_start = Clock::get()?.unix_timestamp;
+
+        // The season's pool inflow, plus whatever went unclaimed, is paid out to its enrollees
+        protocol.honest_payout = protocol
+            .honest_pool_accrued
+            .checked_add(protocol.honest_payout_remaining)
+            .ok_or(CovenantError::MathOverflow)?;
+        protocol.honest_payout_remaining = protocol.honest_payout;
+        protocol.honest_payout_weight = protocol.honest_pool_weight;
+        protocol.honest_pool_accrued = 0;
+        protocol.honest_pool_weight = 0;
+
+        emit!(SeasonAdvanced {
+            season: protocol.season,
+            season_start: protocol.season_start,
+        });
+        msg!("Reputation season {} started", protocol.season);
+        Ok(())
+    }
+
+    /// Set the share (bps) of season counters carried into the next season
+    pub fn set_season_carryover(ctx: Context<UpdateConfig>, carryover_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(carryover_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.season_carryover_bps = carryover_bps;
+
+        msg!("Season carryover set to {} bps", carryover_bps);
+        Ok(())
+    }
+
+    /// Route `pool_bps` of every slash's non-reporter remainder into the honest-provider pool
+    /// (authority or config manager). 0 turns the mode off; rewards already accrued stay claimable.
+    pub fn set_honest_pool(ctx: Context<SetHonestPool>, pool_bps: u16) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(pool_bps <= 10_000, CovenantError::InvalidBasisPoints);
+
+        // Fund the pool's rent-exempt reserve so small shares can always land in it
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.honest_pool.lamports());
+        if pool_bps > 0 {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.honest_pool.to_account_info(),
+                rent_shortfall,
+            )?;
+        }
+        ctx.accounts.protocol.honest_pool_bps = pool_bps;
+
+        msg!("Honest-provider pool set to {} bps of each slash", pool_bps);
+        Ok(())
+    }
+
+    /// Enroll a violation-free, serving provider in the current season's honest-provider
+    /// payout, weighted by its reputation (provider authority only). The pool collected
+    /// this season is claimable with `claim_honest_reward` once the season advances.
+    pub fn enroll_honest_provider(ctx: Context<EnrollHonestProvider>) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let provider = &mut ctx.accounts.provider;
+
+        require!(protocol.honest_pool_bps > 0, CovenantError::HonestPoolDisabled);
+        require!(
+            !(provider.honest_weight > 0 && provider.honest_season + 1 == protocol.season),
+            CovenantError::HonestRewardUnclaimed
+        );
+        require!(
+            provider.is_serving()
+                && provider.confirmed_violations == 0
+                && provider.pending_violations == 0
+                && provider.reputation_score > 0
+                && !(provider.honest_weight > 0 && provider.honest_season == protocol.season),
+            CovenantError::HonestRewardIneligible
+        );
+
+        provider.honest_season = protocol.season;
+        provider.honest_weight = provider.reputation_score;
+        protocol.honest_pool_weight = protocol
+            .honest_pool_weight
+            .checked_add(provider.honest_weight)
+            .ok_or(CovenantError::MathOverflow)?;
+
+        emit!(HonestProviderEnrolled {
+            provider: provider.key(),
+            season: protocol.season,
+            weight: provider.honest_weight,
+        });
+        msg!("Provider enrolled in season {} honest-provider pool", protocol.season);
+        Ok(())
+    }
+
+    /// Claim the provider's reputation-weighted share of the honest-provider pool for the
+    /// season it enrolled in (provider authority only). Only claimable during the following
+    /// season, and forfeited (rolling over to the next payout) if the provider has had a
+    /// violation since enrolling.
+    pub fn claim_honest_reward(ctx: Context<ClaimHonestReward>) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let provider = &mut ctx.accounts.provider;
+
+        require!(
+            provider.honest_weight > 0
+                && provider.honest_season + 1 == protocol.season
+                && provider.confirmed_violations == 0
+                && provider.pending_violations == 0,
+            CovenantError::HonestRewardIneligible
+        );
+
+        let spare = ctx
+            .accounts
+            .honest_pool
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let amount = honest_reward(provider.honest_weight, protocol).min(spare);
+        provider.honest_weight = 0;
+        protocol.honest_payout_remaining -= amount;
+
+        if amount > 0 {
+            let pool_seeds = &[b"honest_pool".as_ref(), &[ctx.bumps.honest_pool]];
+            let signer_seeds = &[&pool_seeds[..]];
+            let cpi_context = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.honest_pool.to_account_info(),
+                    to: ctx.accounts.authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            system_program::transfer(cpi_context, amount)?;
+        }
+
+        emit!(HonestRewardClaimed {
+            provider: provider.key(),
+            season: provider.honest_season,
+            amount,
+        });
+        msg!("Claimed {} lamports from the honest-provider pool", amount);
+        Ok(())
+    }
+
+    /// Halt `slash` alone, e.g. while slash logic is being migrated (authority only)
+    pub fn freeze_slashing(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.protocol.slashing_frozen = true;
+
+        msg!("Slashing frozen");
+        Ok(())
+    }
+
+    /// Resume slashing after a freeze (authority only)
+    pub fn unfreeze_slashing(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.protocol.slashing_frozen = false;
+
+        msg!("Slashing unfrozen");
+        Ok(())
+    }
+
+    /// Configure the treasury-funded reward for permissionless upkeep calls (authority or
+    /// config manager). `budget` caps the total paid out; each caller waits `cooldown_seconds`
+    /// between rewards.
+    pub fn set_crank_rewards(
+        ctx: Context<UpdateConfig>,
+        reward: u64,
+        budget: u64,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.protocol.require_config_editor(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        require!(cooldown_seconds >= 0, CovenantError::InvalidDuration);
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.crank_reward = reward;
+        protocol.crank_budget = budget;
+        protocol.crank_cooldown_seconds = cooldown_seconds;
+
+        msg!("Crank reward set to {} lamports, budget {}", reward, budget);
+        Ok(())
+    }
+
+    /// Pause slashing and violation reporting protocol-wide (authority only)
+    pub fn pause_protocol(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.protocol.paused = true;
+
+        msg!("Protocol paused");
+        Ok(())
+    }
+
+    /// Resume the protocol after review (authority only). A tripped circuit breaker
+    /// is never reset automatically.
+    pub fn unpause_protocol(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.paused = false;
+        protocol.slashed_in_current_window = 0;
+        protocol.window_start = Clock::get()?.unix_timestamp;
+
+        msg!("Protocol unpaused");
+        Ok(())
+    }
+
+    /// Replace the provider's typed capability declarations (discovery metadata only)
+    pub fn set_capabilities(ctx: Context<UpdateProvider>, capabilities: Vec<Capability>) -> Result<()> {
+        require!(capabilities.len() <= MAX_CAPABILITIES, CovenantError::TooManyCapabilities);
+        for (i, capability) in capabilities.iter().enumerate() {
+            require!(
+                capabilities[..i].iter().all(|c| c.key != capability.key),
+                CovenantError::DuplicateCapability
+            );
+        }
+
+        let provider = &mut ctx.accounts.provider;
+        provider.capabilities = capabilities;
+
+        msg!("Provider declared {} capabilities", provider.capabilities.len());
+        Ok(())
+    }
+
+    /// Announce a future maintenance window, during which uptime and availability
+    /// violations can't be reported. Windows must start in the future.
+    pub fn declare_maintenance(ctx: Context<DeclareMaintenance>, start: i64, end: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(start > now && end > start, CovenantError::InvalidMaintenanceWindow);
+
+        let schedule = &mut ctx.accounts.maintenance;
+        schedule.provider = ctx.accounts.provider.key();
+        schedule.bump = ctx.bumps.maintenance;
+
+        // Windows free up their slots once no report can still fall inside them, i.e. once
+        // they ended longer ago than evidence stays actionable (never, with expiry disabled)
+        let ttl_seconds = ctx.accounts.protocol.evidence_ttl_seconds;
+        schedule
+            .windows
+            .retain(|window| evidence_expiry(window.end, ttl_seconds) > now);
+        require!(
+            schedule.windows.len() < MAX_MAINTENANCE_WINDOWS,
+            CovenantError::TooManyMaintenanceWindows
+        );
+        schedule.windows.push(MaintenancePeriod { start, end });
+
+        emit!(MaintenanceDeclared {
+            provider: schedule.provider,
+            start,
+            end,
+        });
+        msg!("Maintenance declared from {} to {}", start, end);
+        Ok(())
+    }
+
+    /// Commit to an off-chain alerting endpoint by its hash; provider-run listeners
+    /// match the WebhookUpdated event against the URL they hold off-chain
+    pub fn set_webhook(ctx: Context<UpdateProvider>, webhook_hash: [u8; 32]) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        provider.webhook_hash = webhook_hash;
+
+        emit!(WebhookUpdated {
+            provider: provider.key(),
+            webhook_hash,
+        });
+        msg!("Provider webhook updated");
+        Ok(())
+    }
+
+    /// Update the provider's declared region (discovery metadata only)
+    pub fn set_region(ctx: Context<UpdateProvider>, region: [u8; 2]) -> Result<()> {
+        require!(is_valid_region(&region), CovenantError::InvalidRegion);
+
+        let provider = &mut ctx.accounts.provider;
+        provider.region = region;
+
+        emit!(RegionUpdated {
+            provider: provider.key(),
+            region,
+        });
+        msg!("Provider region updated");
+        Ok(())
+    }
+
+    /// Point the provider at a new service endpoint and the API version it speaks
+    pub fn update_endpoint(
+        ctx: Context<UpdateProvider>,
+        service_endpoint: String,
+        endpoint_version: u16,
+    ) -> Result<()> {
+        require!(service_endpoint.len() <= 256, CovenantError::EndpointTooLong);
+
+        let provider = &mut ctx.accounts.provider;
+        provider.service_endpoint = service_endpoint;
+        provider.endpoint_version = endpoint_version;
+
+        emit!(EndpointUpdated {
+            provider: provider.key(),
+            endpoint_version,
+        });
+        msg!("Provider endpoint updated to version {}", endpoint_version);
+        Ok(())
+    }
+
+    /// Make this provider a sub-provider of `parent_provider`, inheriting its SLA.
+    /// Both authorities must sign, and hierarchies are one level deep so cycles can't form.
+    pub fn set_parent_provider(ctx: Context<SetParentProvider>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let parent = &mut ctx.accounts.parent_provider;
+
+        require_keys_neq!(provider.key(), parent.key(), CovenantError::InvalidParent);
+        require!(provider.parent.is_none(), CovenantError::InvalidParent);
+        require!(provider.sub_provider_count == 0, CovenantError::InvalidParent);
+        require!(parent.parent.is_none(), CovenantError::InvalidParent);
+        // Sub-providers are slashed under the parent's SLA, which a pending parent can't have yet
+        require!(parent.is_serving(), CovenantError::ProviderInactive);
+
+        provider.parent = Some(parent.key());
+        parent.sub_provider_count += 1;
+
+        msg!("Parent provider set");
+        Ok(())
+    }
+
+    /// Detach a sub-provider from its parent
+    pub fn clear_parent_provider(ctx: Context<ClearParentProvider>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let parent = &mut ctx.accounts.parent_provider;
+
+        provider.parent = None;
+        parent.sub_provider_count -= 1;
+
+        msg!("Parent provider cleared");
+        Ok(())
+    }
+
+    /// Create an organization that providers can join to share a group identity
+    pub fn create_organization(ctx: Context<CreateOrganization>, name: String) -> Result<()> {
+        require!(name.len() <= 64, CovenantError::NameTooLong);
+
+        let organization = &mut ctx.accounts.organization;
+        organization.authority = ctx.accounts.authority.key();
+        organization.name = name;
+        organization.member_count = 0;
+        organization.total_stake = 0;
+        organization.total_violations = 0;
+        organization.bump = ctx.bumps.organization;
+
+        msg!("Organization created");
+        Ok(())
+    }
+
+    /// Add a provider to an organization. Both the provider and organization authorities must
+    /// sign, and a provider can belong to only one organization at a time.
+    pub fn join_organization(ctx: Context<JoinOrganization>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let organization = &mut ctx.accounts.organization;
+
+        require!(provider.org.is_none(), CovenantError::AlreadyInOrganization);
+
+        provider.org = Some(organization.key());
+        provider.org_synced_stake = provider.stake_amount;
+        provider.org_synced_violations = provider.violations;
+        organization.member_count += 1;
+        organization.total_stake += provider.stake_amount;
+        organization.total_violations += provider.violations;
+
+        msg!("Provider joined organization");
+        Ok(())
+    }
+
+    /// Remove a provider from its organization, withdrawing its contribution to the aggregates
+    pub fn leave_organization(ctx: Context<LeaveOrganization>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let organization = &mut ctx.accounts.organization;
+
+        organization.member_count -= 1;
+        organization.total_stake -= provider.org_synced_stake;
+        organization.total_violations -= provider.org_synced_violations;
+        provider.org = None;
+        provider.org_synced_stake = 0;
+        provider.org_synced_violations = 0;
+
+        msg!("Provider left organization");
+        Ok(())
+    }
+
+    /// Fold a member's stake and violation changes since its last sync into the
+    /// organization's aggregates (permissionless)
+    pub fn sync_organization_member(ctx: Context<SyncOrganizationMember>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let organization = &mut ctx.accounts.organization;
+
+        organization.total_stake =
+            organization.total_stake - provider.org_synced_stake + provider.stake_amount;
+        organization.total_violations =
+            organization.total_violations - provider.org_synced_violations + provider.violations;
+        provider.org_synced_stake = provider.stake_amount;
+        provider.org_synced_violations = provider.violations;
+
+        msg!("Organization member synced");
+        Ok(())
+    }
+
+    /// Pre-authorize automatic top-ups from the provider's funding PDA up to `target` lamports of stake
+    pub fn configure_auto_topup(ctx: Context<UpdateProvider>, target: u64) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let (funding_source, _) =
+            Pubkey::find_program_address(&[b"funding", provider.key().as_ref()], ctx.program_id);
+        provider.funding_source = funding_source;
+        provider.auto_topup_target = target;
+
+        msg!("Auto top-up target set to {} lamports", target);
+        Ok(())
+    }
+
+    /// Lock `amount` of the provider's stake under a linear vesting schedule starting now.
+    /// Nothing unlocks before the cliff; everything is unlocked after `duration_seconds`.
+    pub fn set_vesting_schedule(
+        ctx: Context<UpdateProvider>,
+        amount: u64,
+        cliff_seconds: i64,
+        duration_seconds: i64,
+    ) -> Result<()> {
+        require!(
+            cliff_seconds >= 0 && cliff_seconds <= duration_seconds,
+            CovenantError::InvalidVestingSchedule
+        );
+
+        let provider = &mut ctx.accounts.provider;
+        let now = Clock::get()?.unix_timestamp;
+        require!(provider.vesting.locked_at(now) == 0, CovenantError::VestingScheduleActive);
+        require!(amount <= provider.stake_amount, CovenantError::InsufficientStake);
+
+        provider.vesting = VestingSchedule {
+            amount,
+            start: now,
+            cliff_seconds,
+            duration_seconds,
+        };
+
+        msg!("Locked {} lamports vesting over {} seconds", amount, duration_seconds);
+        Ok(())
+    }
+
+    /// Top up a provider's stake from its funding PDA (permissionless) after a slash, or an
+    /// import's first stake. Moves as much as the funding source can spare, up to the
+    /// provider's target. Banned providers and those awaiting approval can't be topped up.
+    pub fn trigger_topup(ctx: Context<TriggerTopup>) -> Result<()> {
+        let provider = &mut ctx.accounts.provider;
+        let protocol = &mut ctx.accounts.protocol;
+        let funding_source = &ctx.accounts.funding_source;
+        require!(!protocol.paused, CovenantError::ProtocolPaused);
+        require!(provider.status != ProviderStatus::Banned, CovenantError::ProviderBlacklisted);
+        require!(
+            provider.status != ProviderStatus::PendingApproval,
+            CovenantError::ProviderPendingApproval
+        );
+
+        let needed = provider.auto_topup_target.saturating_sub(provider.stake_amount);
+        let available = funding_source
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let amount = std::cmp::min(needed, available);
+        require!(provider.topup_armed && amount > 0, CovenantError::TopUpUnavailable);
+
+        sign_funding_transfer(
+            &ctx.accounts.system_program,
+            funding_source,
+            ctx.accounts.stake_vault.to_account_info(),
+            &provider.key(),
+            ctx.bumps.funding_source,
+            amount,
+        )?;
+
+        // A provider without stake left the provider count when it was drained
+        if provider.stake_amount == 0 {
+            protocol.total_providers += 1;
+        }
+        provider.stake_amount += amount;
+        protocol.total_staked += amount;
+        // A partial top-up stays armed so it can resume once the funding source is refilled
+        if provider.stake_amount >= provider.auto_topup_target {
+            provider.topup_armed = false;
+        }
+
+        // Restore a provider that was deactivated by a slash, on probation if it has a record
+        if provider.status == ProviderStatus::Inactive && provider.stake_amount >= MIN_STAKE {
+            provider.status = if provider.violations > 0 {
+                ProviderStatus::Probation
+            } else {
+                ProviderStatus::Active
+            };
+            msg!("Provider reactivated");
+        }
+        provider.refresh_reputation(protocol)?;
+        update_stake_warning(provider, protocol);
+
+        emit!(StakeToppedUp {
+            provider: provider.key(),
+            amount,
+            stake_amount: provider.stake_amount,
+        });
+        msg!("Topped up {} lamports", amount);
+        Ok(())
+    }
+
+    /// Take lamports back out of the provider's funding PDA (provider authority only). The
+    /// PDA must keep its rent-exempt reserve unless it is emptied entirely.
+    pub fn withdraw_funding(ctx: Context<WithdrawFunding>, amount: u64) -> Result<()> {
+        let funding_source = &ctx.accounts.funding_source;
+        let remaining = funding_source
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(CovenantError::InsufficientFunding)?;
+        require!(
+            remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+            CovenantError::FundingRentReserve
+        );
+
+        sign_funding_transfer(
+            &ctx.accounts.system_program,
+            funding_source,
+            ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.provider.key(),
+            ctx.bumps.funding_source,
+            amount,
+        )?;
+
+        emit!(FundingWithdrawn {
+            provider: ctx.accounts.provider.key(),
+            amount,
+            remaining,
+        });
+        msg!("Withdrew {} lamports from the funding source", amount);
+        Ok(())
+    }
+
+    /// Sweep lamports stranded in closed providers' stake vaults and funding PDAs into the
+    /// treasury (authority only). Each entry of `provider_authorities` is matched, in order, by
+    /// three trailing remaining accounts (after any multisig co-signers): the closed provider
+    /// PDA, its stake vault and its funding PDA, whose derivations are all checked.
+    pub fn sweep_to_treasury<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepToTreasury<'info>>,
+        provider_authorities: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.protocol.require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+        let first_source = ctx
+            .remaining_accounts
+            .len()
+            .checked_sub(provider_authorities.len() * 3)
+            .ok_or(CovenantError::InvalidSweepSource)?;
+
+        let treasury = ctx.accounts.treasury.to_account_info();
+        let mut total: u64 = 0;
+        for (provider_authority, sources) in provider_authorities
+            .iter()
+            .zip(ctx.remaining_accounts[first_source..].chunks(3))
+        {
+            let [provider, stake_vault, funding_source] = sources else {
+                return err!(CovenantError::InvalidSweepSource);
+            };
+            let (provider_key, _) =
+                Pubkey::find_program_address(&[b"provider", provider_authority.as_ref()], ctx.program_id);
+            let (vault_key, vault_bump) =
+                Pubkey::find_program_address(&[b"vault", provider_authority.as_ref()], ctx.program_id);
+            let (funding_key, funding_bump) =
+                Pubkey::find_program_address(&[b"funding", provider_key.as_ref()], ctx.program_id);
+            require_keys_eq!(provider.key(), provider_key, CovenantError::InvalidSweepSource);
+            require_keys_eq!(stake_vault.key(), vault_key, CovenantError::InvalidSweepSource);
+            require_keys_eq!(funding_source.key(), funding_key, CovenantError::InvalidSweepSource);
+            // A registered provider's vault and funding PDA still belong to it
+            require!(provider.owner != &crate::ID, CovenantError::InvalidSweepSource);
+
+            let vault_balance = stake_vault.lamports();
+            if vault_balance > 0 {
+                sign_vault_transfer(
+                    &ctx.accounts.system_program,
+                    stake_vault,
+                    treasury.clone(),
+                    provider_authority,
+                    vault_bump,
+                    vault_balance,
+                )?;
+            }
+            let funding_balance = funding_source.lamports();
+            if funding_balance > 0 {
+                sign_funding_transfer(
+                    &ctx.accounts.system_program,
+                    funding_source,
+                    treasury.clone(),
+                    &provider_key,
+                    funding_bump,
+                    funding_balance,
+                )?;
+            }
+            total = total
+                .checked_add(vault_balance)
+                .and_then(|total| total.checked_add(funding_balance))
+                .ok_or(CovenantError::MathOverflow)?;
+        }
+
+        emit!(TreasurySwept {
+            providers: provider_authorities.len() as u32,
+            total,
+        });
+        msg!("Swept {} lamports into the treasury", total);
+        Ok(())
+    }
+}
+
+// Constants
+pub const MIN_STAKE: u64 = 100_000_000; // 0.1 SOL minimum stake
+// Reputation penalty per violation, indexed by ViolationType
+pub const DEFAULT_VIOLATION_WEIGHTS: [u16; 5] = [
+    2, // UptimeViolation
+    2, // ResponseTimeViolation
+    3, // AccuracyViolation
+    4, // ServiceUnavailable
+    1, // Other
+];
+
+// Reputation bonus per full multiple of the minimum stake held above it
+pub const OVERCOLLATERALIZATION_BONUS_BPS_PER_MULTIPLE: u64 = 1_000;
+pub const DEFAULT_OVERCOLLATERALIZATION_BONUS_CAP_BPS: u16 = 2_000;
+
+// Share of the SLA penalty charged when a provider acknowledges a violation
+pub const DEFAULT_SETTLEMENT_DISCOUNT_BPS: u16 = 5_000;
+
+// Maximum number of keys in the protocol authority multisig
+pub const MAX_AUTHORITIES: usize = 10;
+
+// How long violation evidence stays actionable before it can be expired
+pub const DEFAULT_EVIDENCE_TTL_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+// Fixed-point scale of reputation scores: one clean success is worth 10_000
+pub const REPUTATION_SCALE: u64 = 10_000;
+
+// Violation descriptions: accounts are sized for the hard maximum, the protocol enforces its own limit
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+pub const DEFAULT_DESCRIPTION_LEN: u16 = 512;
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+
+// Highest anti-spam fee the protocol may charge per violation report
+pub const MAX_REPORT_FEE: u64 = 10_000_000; // 0.01 SOL
+
+// Rolling window over which slash volume is measured for the circuit breaker
+pub const CIRCUIT_BREAKER_WINDOW_SECONDS: i64 = 24 * 60 * 60; // 1 day
+
+// Default period after which recent slashing (`slashed_rolling`) decays by half
+pub const DEFAULT_ROLLING_WINDOW_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+// Reputation tiers for withdrawal limits, and the default limit period
+pub const WITHDRAWAL_TIERS: usize = 4;
+pub const DEFAULT_WITHDRAWAL_PERIOD_SECONDS: i64 = 24 * 60 * 60; // 1 day
+
+// Notice consumers get before an SLA penalty decrease takes effect
+pub const PENALTY_DECREASE_NOTICE_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+// Maximum number of upcoming maintenance windows a provider can declare
+pub const MAX_MAINTENANCE_WINDOWS: usize = 4;
+
+// Number of recent record_success request ids remembered for deduplication
+pub const RECENT_REQUEST_IDS: usize = 8;
+
+// Number of recent violation times remembered for time-weighted reputation decay
+pub const RECENT_VIOLATIONS: usize = 16;
+
+// Maximum number of capability declarations per provider
+pub const MAX_CAPABILITIES: usize = 8;
+
+// Number of distinct reporter keys remembered per provider for the reputation-floor quorum
+pub const MAX_TRACKED_REPORTERS: usize = 8;
+
+// Account sizes shared by the account constraints and `estimate_onboarding_cost`
+pub const PROVIDER_SPACE: usize = 8 + 32 + 4 + 64 + 4 + 256 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 * 5 + 8 + 1 + 2 + 8 + 2 + 16 * RECENT_REQUEST_IDS + 1 + 33 + 4 + 32 + 8 + 2 + 8 + 8 + 32 + 4 + 9 * MAX_CAPABILITIES + 1 + 8 + 33 + 8 + 8 + 32 + 1 + 8 + 8 + 4 + 8 + 8 * 5 + 8 + 8 + 1 + 9 * RECENT_VIOLATIONS + 1 + 1 + 4 + 4 + 4 + 8 + 8 + 32 * MAX_TRACKED_REPORTERS + 1;
+pub const SLA_SPACE: usize = 8 + 32 + 1 + 4 + 1 + 1 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 8;
+pub const NAME_RECORD_SPACE: usize = 8 + 32 + 32 + 1;
+pub const VIOLATION_SPACE: usize = 8 + 32 + 32 + 1 + 32 + 4 + MAX_DESCRIPTION_LEN + 8 + 1 + 1 + 1 + 8 + 1 + 4 + MAX_EVIDENCE_URI_LEN + 4 + MAX_CLAIMS + 1 + 8 + 8 + 8 + 1 + 32 + 1 + 32 + 32 + 8 + 32;
+
+// Well-known capability keys; values are key-specific (ids, token counts, flags)
+pub const CAPABILITY_MODEL_ID: u8 = 0;
+pub const CAPABILITY_MAX_CONTEXT_TOKENS: u8 = 1;
+pub const CAPABILITY_MAX_OUTPUT_TOKENS: u8 = 2;
+pub const CAPABILITY_STREAMING: u8 = 3;
+pub const CAPABILITY_TOOL_USE: u8 = 4;
+
+// Maximum number of claim codes bundled in one violation report
+pub const MAX_CLAIMS: usize = 8;
+
+// Reputation penalty per unresolved complaint, capped so complaints alone can't zero a score
+pub const COMPLAINT_PENALTY_BPS: u64 = 500;
+pub const MAX_COMPLAINT_PENALTY_BPS: u64 = 5_000;
+
+// Share of season counters carried into the next season (the rest resets)
+pub const DEFAULT_SEASON_CARRYOVER_BPS: u16 = 5_000;
+// Seasons decayed one by one when a provider catches up; beyond this the counters reset
+pub const MAX_SEASON_CATCH_UP: u32 = 32;
+
+// Helpers
+
+/// Highest stake amount that still counts as "near minimum" for StakeLow warnings
+pub fn stake_warning_level(threshold_bps: u16) -> u64 {
+    MIN_STAKE + MIN_STAKE * threshold_bps as u64 / 10_000
+}
+
+/// Reputation from successful requests, discounted by the weighted violation count.
+/// Fixed-point, scaled by REPUTATION_SCALE so close scores don't truncate to the same value.
+///
+/// Each violation's weight fades with its age (see `violation_decay_bps`). A type's newest
+/// counted violations take their times from the `recent` stamps; any older than every stamp
+/// are aged like the oldest one (or like `last_violation_at` when nothing is stamped).
+pub fn compute_reputation(
+    successful_requests: u64,
+    violation_counts: &[u64; 5],
+    weights: &[u16; 5],
+    recent: &[ViolationStamp],
+    last_violation_at: i64,
+    now: i64,
+    decay_seconds: i64,
+) -> u64 {
+    let stamped = recent.iter().filter(|stamp| stamp.at != 0);
+    let oldest = stamped.clone().map(|stamp| stamp.at).min().unwrap_or(last_violation_at);
+    let oldest_bps = violation_decay_bps(now.saturating_sub(oldest), decay_seconds) as u128;
+
+    let mut penalty_bps = 0u128;
+    for (index, (count, weight)) in violation_counts.iter().zip(weights.iter()).enumerate() {
+        let mut times: Vec<i64> = stamped
+            .clone()
+            .filter(|stamp| stamp.violation_type as usize == index)
+            .map(|stamp| stamp.at)
+            .collect();
+        times.sort_unstable_by(|a, b| b.cmp(a));
+        times.truncate(*count as usize);
+
+        let stamped_bps = times
+            .iter()
+            .map(|at| violation_decay_bps(now.saturating_sub(*at), decay_seconds) as u128)
+            .sum::<u128>();
+        let unstamped_bps = (*count - times.len() as u64) as u128 * oldest_bps;
+        penalty_bps += (stamped_bps + unstamped_bps) * *weight as u128;
+    }
+
+    let scaled = successful_requests as u128 * REPUTATION_SCALE as u128 * 10_000 / (penalty_bps + 10_000);
+    u64::try_from(scaled).unwrap_or(u64::MAX)
+}
+
+/// Weight (bps) a violation `age_seconds` old still carries against reputation: full when
+/// new, falling linearly to nothing once it is `decay_seconds` old (0 disables decay)
+pub fn violation_decay_bps(age_seconds: i64, decay_seconds: i64) -> u64 {
+    if decay_seconds <= 0 {
+        return 10_000;
+    }
+    let remaining = decay_seconds.saturating_sub(age_seconds.max(0)).max(0);
+    (remaining as u128 * 10_000 / decay_seconds as u128) as u64
+}
+
+/// Share (bps) of a reporter's resolved reports that were confirmed (0 before any resolve)
+pub fn reporter_accuracy_bps(confirmed_reports: u64, dismissed_reports: u64) -> u64 {
+    let resolved = confirmed_reports as u128 + dismissed_reports as u128;
+    if resolved == 0 {
+        return 0;
+    }
+    (confirmed_reports as u128 * 10_000 / resolved) as u64
+}
+
+/// Share (bps) of all stake ever at risk that has been slashed
+pub fn slash_rate_bps(total_slashed: u64, total_staked: u64) -> u64 {
+    let at_risk = total_slashed as u128 + total_staked as u128;
+    if at_risk == 0 {
+        return 0;
+    }
+    (total_slashed as u128 * 10_000 / at_risk) as u64
+}
+
+/// Slash amount after capping it so the provider keeps at least `protected_balance` staked
+pub fn protected_slash_amount(slash_amount: u64, stake_amount: u64, protected_balance: u64) -> u64 {
+    std::cmp::min(slash_amount, stake_amount.saturating_sub(protected_balance))
+}
+
+/// Largest slash one violation may take: `max_single_slash_bps` of the current stake
+pub fn max_single_slash(stake_amount: u64, max_single_slash_bps: u16) -> u64 {
+    (stake_amount as u128 * max_single_slash_bps as u128 / 10_000) as u64
+}
+
+/// Limit a requested slash by the protected balance, the single-slash ceiling and the minimum slash
+pub fn limit_slash(requested: u64, stake_amount: u64, protocol: &Protocol) -> Result<u64> {
+    let amount = protected_slash_amount(requested, stake_amount, protocol.protected_balance)
+        .min(max_single_slash(stake_amount, protocol.max_single_slash_bps));
+    apply_min_slash(amount, stake_amount, protocol)
+}
+
+/// Handle slashes below `min_slash_amount`: a provider already under the minimum stake loses
+/// whatever it has left above the protected balance (still within the single-slash ceiling),
+/// anyone else's dust slash is rejected
+pub fn apply_min_slash(amount: u64, stake_amount: u64, protocol: &Protocol) -> Result<u64> {
+    if amount >= protocol.min_slash_amount {
+        return Ok(amount);
+    }
+    require!(stake_amount < MIN_STAKE, CovenantError::SlashTooSmall);
+    Ok(stake_amount
+        .saturating_sub(protocol.protected_balance)
+        .min(max_single_slash(stake_amount, protocol.max_single_slash_bps)))
+}
+
+/// Reputation bonus (bps) for stake above the minimum: 10% per extra multiple of the minimum, capped
+pub fn overcollateralization_bonus_bps(stake_amount: u64, minimum: u64, cap_bps: u16) -> u16 {
+    if minimum == 0 || stake_amount <= minimum {
+        return 0;
+    }
+
+    let bonus = ((stake_amount - minimum) as u128)
+        .saturating_mul(OVERCOLLATERALIZATION_BONUS_BPS_PER_MULTIPLE as u128)
+        / minimum as u128;
+
+    std::cmp::min(bonus, cap_bps as u128) as u16
+}
+
+/// Reputation penalty (bps) for a provider's unresolved complaints
+pub fn complaint_penalty_bps(open_complaints: u64) -> u64 {
+    open_complaints
+        .saturating_mul(COMPLAINT_PENALTY_BPS)
+        .min(MAX_COMPLAINT_PENALTY_BPS)
+}
+
+/// Share of a season counter carried into the next season
+pub fn carry_over(count: u64, carryover_bps: u16) -> u64 {
+    (count as u128 * carryover_bps as u128 / 10_000) as u64
+}
+
+/// Share (bps) of the SLA penalty owed for a breach lasting `duration_seconds`: `bps_per_hour`
+/// for every started hour (at least one), capped at `cap_bps`. A rate of 0 charges the full penalty.
+pub fn duration_penalty_bps(duration_seconds: i64, bps_per_hour: u16, cap_bps: u16) -> u64 {
+    if bps_per_hour == 0 {
+        return 10_000;
+    }
+    let hours = (duration_seconds.max(0) as u64).div_ceil(3_600).max(1);
+    hours
+        .saturating_mul(bps_per_hour as u64)
+        .min(cap_bps as u64)
+}
+
+/// Scale a penalty amount by the protocol's duration schedule
+pub fn scale_by_duration(amount: u64, duration_seconds: i64, protocol: &Protocol) -> u64 {
+    let bps = duration_penalty_bps(
+        duration_seconds,
+        protocol.duration_penalty_bps_per_hour,
+        protocol.max_duration_penalty_bps,
+    );
+    (amount as u128 * bps as u128 / 10_000) as u64
+}
+
+/// Penalty for a violation before claims are shared out and the slash limits apply: the SLA
+/// penalty on the stake, scaled by the breach's duration, plus the first-reporter bonus
+pub fn violation_penalty(
+    stake_amount: u64,
+    penalty_percentage: u8,
+    duration_seconds: i64,
+    first_of_type: bool,
+    protocol: &Protocol,
+) -> u64 {
+    let amount = (stake_amount as u128 * penalty_percentage as u128 / 100) as u64;
+    let amount = scale_by_duration(amount, duration_seconds, protocol);
+    if first_of_type {
+        amount.saturating_add(
+            (amount as u128 * protocol.first_reporter_bonus_bps as u128 / 10_000) as u64,
+        )
+    } else {
+        amount
+    }
+}
+
+/// Extra penalty on top of `amount` for a breach the provider attested as compliant
+pub fn false_attestation_penalty(amount: u64, protocol: &Protocol) -> u64 {
+    (amount as u128 * protocol.false_attestation_penalty_bps as u128 / 10_000) as u64
+}
+
+/// Reporter's share of a slash under the protocol cap (0 means uncapped)
+pub fn reporter_compensation(slash_amount: u64, max_compensation: u64) -> u64 {
+    if max_compensation == 0 {
+        slash_amount
+    } else {
+        slash_amount.min(max_compensation)
+    }
+}
+
+/// Timestamp after which a violation reported at `reported_at` can be expired
+pub fn evidence_expiry(reported_at: i64, ttl_seconds: i64) -> i64 {
+    if ttl_seconds == 0 {
+        return i64::MAX;
+    }
+    reported_at.saturating_add(ttl_seconds)
+}
+
+/// Portion of `total` vested at `now` under a linear schedule with a cliff
+pub fn vested_amount(total: u64, start: i64, cliff_seconds: i64, duration_seconds: i64, now: i64) -> u64 {
+    let elapsed = now.saturating_sub(start);
+    if elapsed < cliff_seconds {
+        return 0;
+    }
+    if elapsed >= duration_seconds {
+        return total;
+    }
+    (total as u128 * elapsed as u128 / duration_seconds as u128) as u64
+}
+
+/// True when a slash payout to `reporter` would stay within the provider's own family:
+/// the reporter is its authority, the authority of its parent provider, or the authority of
+/// its organization. Who funds a provider's top-up PDA isn't recorded on-chain, so shared
+/// funding can't be detected here.
+pub fn is_self_dealing(reporter: &Pubkey, provider: &Provider) -> bool {
+    reporter == &provider.authority
+        || provider.parent.is_some_and(|parent| is_provider_of(reporter, &parent))
+        || provider.org.is_some_and(|org| is_organization_of(reporter, &org))
+}
+
+/// True when `organization` is the organization PDA created by `authority`
+pub fn is_organization_of(authority: &Pubkey, organization: &Pubkey) -> bool {
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"organization", authority.as_ref()], &crate::ID);
+    &expected == organization
+}
+
+/// True when `provider` is the provider PDA registered by `authority`
+pub fn is_provider_of(authority: &Pubkey, provider: &Pubkey) -> bool {
+    let (expected, _) = Pubkey::find_program_address(&[b"provider", authority.as_ref()], &crate::ID);
+    &expected == provider
+}
+
+/// True when at least `threshold` distinct keys from `authorities` appear among `signers`
+pub fn verify_multisig(signers: &[Pubkey], authorities: &[Pubkey], threshold: u8) -> bool {
+    if threshold == 0 {
+        return false;
+    }
+
+    let approvals = authorities
+        .iter()
+        .enumerate()
+        .filter(|(i, key)| !authorities[..*i].contains(key) && signers.contains(key))
+        .count();
+
+    approvals >= threshold as usize
+}
+
+/// Take a requested slash through `limit_slash`, pay it out of the stake vault (to the reporter
+/// up to the compensation cap, any excess to the treasury less the honest-provider pool's share)
+/// and record it. Returns the amount slashed.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_slash<'info>(
+    protocol: &mut Protocol,
+    provider: &mut Account<'info, Provider>,
+    parent_provider: Option<&mut Account<'info, Provider>>,
+    violation: &mut Violation,
+    requested: u64,
+    system_program: &Program<'info, System>,
+    stake_vault: &AccountInfo<'info>,
+    vault_bump: u8,
+    reporter: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    honest_pool: AccountInfo<'info>,
+) -> Result<u64> {
+    let protected =
+        protected_slash_amount(requested, provider.stake_amount, protocol.protected_balance);
+    if protected < requested && protocol.protected_balance > 0 {
+        emit!(ProtectedBalanceReached {
+            provider: provider.key(),
+            requested,
+            slashed: protected,
+        });
+    }
+    let ceiling = max_single_slash(provider.stake_amount, protocol.max_single_slash_bps);
+    if protected > ceiling {
+        emit!(SlashClamped {
+            provider: provider.key(),
+            requested: protected,
+            slashed: ceiling,
+        });
+    }
+    let amount = limit_slash(requested, provider.stake_amount, protocol)?;
+
+    let compensation = reporter_compensation(amount, protocol.max_reporter_compensation);
+    transfer_from_vault(
+        system_program,
+        stake_vault,
+        reporter,
+        &provider.authority,
+        vault_bump,
+        compensation,
+    )?;
+    let excess = amount - compensation;
+    let pool_share = honest_pool_share(excess, protocol);
+    if pool_share > 0 {
+        transfer_from_vault(
+            system_program,
+            stake_vault,
+            honest_pool,
+            &provider.authority,
+            vault_bump,
+            pool_share,
+        )?;
+        protocol.honest_pool_accrued = protocol
+            .honest_pool_accrued
+            .checked_add(pool_share)
+            .ok_or(CovenantError::MathOverflow)?;
+    }
+    if excess > pool_share {
+        transfer_from_vault(
+            system_program,
+            stake_vault,
+            treasury,
+            &provider.authority,
+            vault_bump,
+            excess - pool_share,
+        )?;
+    }
+
+    record_slash(protocol, provider, parent_provider, violation, amount)?;
+    Ok(amount)
+}
+
+/// Share of a slash's non-reporter remainder routed to the honest-provider pool
+pub fn honest_pool_share(excess: u64, protocol: &Protocol) -> u64 {
+    (excess as u128 * protocol.honest_pool_bps as u128 / 10_000) as u64
+}
+
+/// A provider's cut of last season's honest-provider payout, proportional to the
+/// reputation it enrolled with
+pub fn honest_reward(weight: u64, protocol: &Protocol) -> u64 {
+    if protocol.honest_payout_weight == 0 {
+        return 0;
+    }
+    let share = protocol.honest_payout as u128 * weight as u128 / protocol.honest_payout_weight as u128;
+    (share as u64).min(protocol.honest_payout_remaining)
+}
+
+/// Transfer lamports out of a provider's stake vault without dipping into its rent-exempt reserve
+pub fn transfer_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    stake_vault: &AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    provider_authority: &Pubkey,
+    vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let rent_reserve = Rent::get()?.minimum_balance(0);
+    let remaining = stake_vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(CovenantError::VaultRentReserve)?;
+    require!(remaining >= rent_reserve, CovenantError::VaultRentReserve);
+
+    sign_vault_transfer(system_program, stake_vault, to, provider_authority, vault_bump, amount)
+}
+
+fn sign_vault_transfer<'info>(
+    system_program: &Program<'info, System>,
+    stake_vault: &AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    provider_authority: &Pubkey,
+    vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let vault_seeds = &[
+        b"vault".as_ref(),
+        provider_authority.as_ref(),
+        &[vault_bump],
+    ];
+    let signer_seeds = &[&vault_seeds[..]];
+
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: stake_vault.to_account_info(),
+            to,
+        },
+        signer_seeds,
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Move lamports out of a provider's funding PDA, signing with its seeds
+fn sign_funding_transfer<'info>(
+    system_program: &Program<'info, System>,
+    funding_source: &AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    provider: &Pubkey,
+    funding_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let funding_seeds = &[b"funding".as_ref(), provider.as_ref(), &[funding_bump]];
+    let signer_seeds = &[&funding_seeds[..]];
+
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: funding_source.to_account_info(),
+            to,
+        },
+        signer_seeds,
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Close a program-owned account, moving its lamports to `to`. Accounts that were never
+/// created (or are already closed) are left alone.
+fn close_if_open(account: &AccountInfo, to: &AccountInfo) -> Result<()> {
+    if account.owner != &crate::ID {
+        return Ok(());
+    }
+    to.add_lamports(account.lamports())?;
+    account.sub_lamports(account.lamports())?;
+    account.assign(&system_program::ID);
+    account.resize(0)?;
+    Ok(())
+}
+
+/// Close the provider's self-attestations and sponsorship allowances passed in `accounts`,
+/// refunding their lamports to `to` and taking them off the provider's open counts
+fn close_provider_records(
+    provider_key: Pubkey,
+    provider: &mut Provider,
+    accounts: &[AccountInfo],
+    to: &AccountInfo,
+) -> Result<()> {
+    for account in accounts {
+        require_keys_eq!(*account.owner, crate::ID, CovenantError::InvalidProviderRecord);
+        let data = account.try_borrow_data()?;
+        if let Ok(attestation) = SelfAttestation::try_deserialize(&mut &data[..]) {
+            require_keys_eq!(attestation.provider, provider_key, CovenantError::InvalidProviderRecord);
+            provider.attestations -= 1;
+        } else {
+            let allowance = ReporterAllowance::try_deserialize(&mut &data[..])
+                .map_err(|_| error!(CovenantError::InvalidProviderRecord))?;
+            require_keys_eq!(allowance.provider, provider_key, CovenantError::InvalidProviderRecord);
+            provider.sponsorships -= 1;
+        }
+        drop(data);
+        close_if_open(account, to)?;
+    }
+    Ok(())
+}
+
+/// Cure window for a provider that has lost `disputes_lost` cure reviews: halved for each
+/// loss from the `threshold`-th on (0 disables escalation)
+pub fn escalated_cure_window(window: i64, disputes_lost: u64, threshold: u64) -> i64 {
+    if threshold == 0 || disputes_lost < threshold {
+        return window;
+    }
+    let halvings = (disputes_lost - threshold + 1).min(63) as u32;
+    window >> halvings
+}
+
+/// Pay lamports out of the treasury PDA, signing with its seeds. A no-op for zero.
+pub fn transfer_from_treasury<'info>(
+    system_program: &Program<'info, System>,
+    treasury: &SystemAccount<'info>,
+    treasury_bump: u8,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let treasury_seeds = &[b"treasury".as_ref(), &[treasury_bump]];
+    let signer_seeds = &[&treasury_seeds[..]];
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: treasury.to_account_info(),
+            to,
+        },
+        signer_seeds,
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Split a dismissed report's fee into the refunded and forfeited parts
+pub fn dismissal_refund(report_fee: u64, refund_bps: u16, malicious: bool) -> (u64, u64) {
+    let refunded = if malicious {
+        0
+    } else {
+        (report_fee as u128 * refund_bps as u128 / 10_000) as u64
+    };
+    (refunded, report_fee - refunded)
+}
+
+/// Pay the crank reward from the treasury to an upkeep caller. Skipped (without failing the
+/// upkeep) when rewards are off, the budget or treasury can't cover it, or the caller is cooling down.
+pub fn pay_crank_reward<'info>(
+    protocol: &mut Protocol,
+    cranker_state: &mut CrankerState,
+    treasury: &SystemAccount<'info>,
+    treasury_bump: u8,
+    cranker: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let reward = protocol.crank_reward;
+    if reward == 0 {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if cranker_state.last_crank_at > 0
+        && now.saturating_sub(cranker_state.last_crank_at) < protocol.crank_cooldown_seconds
+    {
+        msg!("Crank reward cooling down");
+        return Ok(());
+    }
+
+    let spare = treasury
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+    if protocol.crank_budget < reward || spare < reward {
+        msg!("Crank reward unavailable");
+        return Ok(());
+    }
+
+    transfer_from_treasury(system_program, treasury, treasury_bump, cranker, reward)?;
+
+    protocol.crank_budget -= reward;
+    cranker_state.last_crank_at = now;
+    cranker_state.total_earned = cranker_state.total_earned.saturating_add(reward);
+
+    emit!(CrankRewardPaid {
+        cranker: cranker_state.cranker,
+        amount: reward,
+        remaining_budget: protocol.crank_budget,
+    });
+    Ok(())
+}
+
+/// Apply a slash's bookkeeping: stake and protocol totals, resolution once every claim is
+/// settled, reputation (including the parent of a sub-provider), probation or deactivation
+/// below minimum stake and StakeLow warnings
+pub fn record_slash(
+    protocol: &mut Protocol,
+    provider: &mut Account<Provider>,
+    parent_provider: Option<&mut Account<Provider>>,
+    violation: &mut Violation,
+    amount: u64,
+) -> Result<()> {
+    provider.stake_amount -= amount;
+    if amount > 0 {
+        provider.topup_armed = true;
+    }
+    protocol.total_slashed += amount;
+    protocol.total_staked -= amount;
+    // A slash that drains the stake takes the provider out of the count, like a full withdrawal
+    if amount > 0 && provider.stake_amount == 0 {
+        protocol.total_providers -= 1;
+    }
+    protocol.record_slash_volume(amount, Clock::get()?.unix_timestamp)?;
+    protocol.record_rolling_slash(amount, Clock::get()?.unix_timestamp);
+
+    // A partially settled violation stays open until its remaining claims are settled
+    let fully_settled = violation.settled_claims == violation.all_claims_mask();
+    if fully_settled {
+        provider.pending_violations -= 1;
+        provider.confirmed_violations += 1;
+        violation.is_resolved = true;
+    }
+    provider.refresh_reputation(protocol)?;
+
+    // A sub-provider's confirmed violation also counts against its parent's reputation
+    if let Some(parent_key) = provider.parent.filter(|_| fully_settled) {
+        let parent = parent_provider.ok_or(CovenantError::InvalidParent)?;
+        require_keys_eq!(parent.key(), parent_key, CovenantError::InvalidParent);
+        parent.sync_season(protocol);
+        parent.violation_counts[violation.violation_type as usize] += 1;
+        parent.season_violation_counts[violation.violation_type as usize] += 1;
+        parent.stamp_violation(violation.violation_type, Clock::get()?.unix_timestamp);
+        parent.refresh_reputation(protocol)?;
+    }
+
+    // Deactivate provider if stake falls below minimum, otherwise put it on probation
+    if provider.status != ProviderStatus::Banned {
+        if provider.stake_amount < MIN_STAKE {
+            provider.status = ProviderStatus::Inactive;
+            msg!("Provider deactivated due to insufficient stake");
+        } else if provider.status == ProviderStatus::Active {
+            provider.status = ProviderStatus::Probation;
+            msg!("Provider placed on probation after slash");
+        }
+    }
+
+    update_stake_warning(provider, protocol);
+    Ok(())
+}
+
+/// Warn once when the stake enters the band just above minimum, re-arming once it leaves
+pub fn update_stake_warning(provider: &mut Account<Provider>, protocol: &Protocol) {
+    let in_warning_band = provider.stake_amount >= MIN_STAKE
+        && provider.stake_amount <= stake_warning_level(protocol.stake_warning_threshold_bps);
+    if in_warning_band && !provider.stake_low_warned {
+        provider.stake_low_warned = true;
+        emit!(StakeLow {
+            provider: provider.key(),
+            stake_amount: provider.stake_amount,
+            min_stake: MIN_STAKE,
+        });
+    } else if !in_warning_band {
+        provider.stake_low_warned = false;
+    }
+}
+
+/// Registry key for a display name, split into two 32-byte PDA seeds. Names compare
+/// case-insensitively, ignoring surrounding whitespace.
+pub fn name_key(name: &str) -> [u8; 64] {
+    let normalized = name.trim().to_ascii_lowercase();
+    let len = normalized.len().min(64);
+    let mut key = [0u8; 64];
+    key[..len].copy_from_slice(&normalized.as_bytes()[..len]);
+    key
+}
+
+/// Create the name's registry PDA for a new provider, failing with `NameTaken` if it exists
+fn claim_name<'info>(
+    name_record: &UncheckedAccount<'info>,
+    bump: u8,
+    name: &str,
+    payer: &Signer<'info>,
+    authority: Pubkey,
+    provider: Pubkey,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    require!(name_record.owner != &crate::ID, CovenantError::NameTaken);
+
+    let key = name_key(name);
+    let record_seeds = &[b"name".as_ref(), &key[..32], &key[32..], &[bump]];
+    let signer_seeds = &[&record_seeds[..]];
+    let space = NAME_RECORD_SPACE;
+
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(name_record.lamports());
+    transfer_lamports(system_program, payer, name_record, rent_shortfall)?;
+    allocate_pda(name_record, space, signer_seeds, system_program)?;
+
+    let record = NameRecord {
+        authority,
+        provider,
+        bump,
+    };
+    record.try_serialize(&mut &mut name_record.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Allocate a funded PDA and assign it to this program, signing with its seeds. Funding,
+/// allocating and assigning separately means lamports sent to the address beforehand can't
+/// block its creation.
+fn allocate_pda<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+/// Transfer lamports from a signer through the system program; a zero amount is a no-op
+fn transfer_lamports<'info>(
+    system_program: &Program<'info, System>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: from.clone(),
+            to: to.clone(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Validate a registration, fund the stake vault and initialize the provider account
+fn open_provider(
+    accounts: &mut RegisterProvider,
+    bumps: &RegisterProviderBumps,
+    name: String,
+    service_endpoint: String,
+    endpoint_version: u16,
+    stake_amount: u64,
+    region: [u8; 2],
+) -> Result<()> {
+    require!(name.len() <= 64, CovenantError::NameTooLong);
+    require!(service_endpoint.len() <= 256, CovenantError::EndpointTooLong);
+    require!(stake_amount >= MIN_STAKE, CovenantError::InsufficientStake);
+    require!(is_valid_region(&region), CovenantError::InvalidRegion);
+
+    if accounts.protocol.unique_names {
+        let name_record = accounts
+            .name_record
+            .as_ref()
+            .ok_or(CovenantError::NameRecordRequired)?;
+        claim_name(
+            name_record,
+            bumps.name_record.ok_or(CovenantError::NameRecordRequired)?,
+            &name,
+            &accounts.provider_authority,
+            accounts.provider_authority.key(),
+            accounts.provider.key(),
+            &accounts.system_program,
+        )?;
+    }
+
+    // Transfer stake to vault, plus whatever the vault still needs for its rent-exempt reserve
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(accounts.stake_vault.lamports());
+    let cpi_context = CpiContext::new(
+        accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: accounts.provider_authority.to_account_info(),
+            to: accounts.stake_vault.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, stake_amount + rent_shortfall)?;
+
+    // Initialize provider account
+    let provider = &mut accounts.provider;
+    provider.authority = accounts.provider_authority.key();
+    provider.name = name;
+    provider.service_endpoint = service_endpoint;
+    provider.stake_amount = stake_amount;
+    provider.violations = 0;
+    provider.successful_requests = 0;
+    provider.created_at = Clock::get()?.unix_timestamp;
+    provider.status = match accounts.protocol.onboarding_mode {
+        OnboardingMode::Open => ProviderStatus::Active,
+        OnboardingMode::Approval => ProviderStatus::PendingApproval,
+    };
+    provider.bump = bumps.provider;
+    provider.stake_low_warned = false;
+    provider.violation_counts = [0; 5];
+    provider.reputation_score = 0;
+    provider.withdrawals_frozen = false;
+    provider.region = region;
+    provider.pending_violations = 0;
+    provider.overcollateralization_bonus_bps = 0;
+    provider.recent_request_ids = [[0; 16]; RECENT_REQUEST_IDS];
+    provider.recent_request_cursor = 0;
+    provider.recent_violations = [ViolationStamp::default(); RECENT_VIOLATIONS];
+    provider.recent_violation_cursor = 0;
+    provider.topup_armed = false;
+    provider.attestations = 0;
+    provider.sponsorships = 0;
+    provider.honest_season = 0;
+    provider.honest_weight = 0;
+    provider.disputes_lost = 0;
+    provider.distinct_reporters = [Pubkey::default(); MAX_TRACKED_REPORTERS];
+    provider.distinct_reporter_count = 0;
+    provider.parent = None;
+    provider.sub_provider_count = 0;
+    provider.funding_source = Pubkey::default();
+    provider.auto_topup_target = 0;
+    provider.endpoint_version = endpoint_version;
+    provider.confirmed_violations = 0;
+    provider.dismissed_violations = 0;
+    provider.vesting = VestingSchedule::default();
+    provider.capabilities = Vec::new();
+    provider.seen_violation_types = 0;
+    provider.last_violation_at = 0;
+    provider.complaints = 0;
+    provider.open_complaints = 0;
+    provider.season = accounts.protocol.season;
+    provider.season_successes = 0;
+    provider.season_violation_counts = [0; 5];
+    provider.withdrawn_this_period = 0;
+    provider.period_start = 0;
+    provider.pre_ban_status = ProviderStatus::Active;
+    provider.org = None;
+    provider.org_synced_stake = 0;
+    provider.org_synced_violations = 0;
+    provider.webhook_hash = [0; 32];
+    provider.imported = false;
+    provider.refresh_reputation(&accounts.protocol)?;
+
+    // Update protocol stats
+    let protocol = &mut accounts.protocol;
+    protocol.total_providers += 1;
+    protocol.total_staked += stake_amount;
+
+    msg!("Provider registered with {} lamports staked", stake_amount);
+    Ok(())
+}
+
+/// Validate SLA terms and write them to a freshly initialized SLA account
+#[allow(clippy::too_many_arguments)]
+fn write_sla(
+    sla: &mut SLA,
+    provider: Pubkey,
+    bump: u8,
+    uptime_guarantee: u8,
+    max_response_time_ms: u32,
+    accuracy_guarantee: u8,
+    penalty_percentage: u8,
+    locked_until: i64,
+) -> Result<()> {
+    require!(uptime_guarantee <= 100, CovenantError::InvalidPercentage);
+    require!(accuracy_guarantee <= 100, CovenantError::InvalidPercentage);
+    require!(penalty_percentage > 0 && penalty_percentage <= 100, CovenantError::InvalidPercentage);
+
+    sla.provider = provider;
+    sla.uptime_guarantee = uptime_guarantee;
+    sla.max_response_time_ms = max_response_time_ms;
+    sla.accuracy_guarantee = accuracy_guarantee;
+    sla.penalty_percentage = penalty_percentage;
+    sla.pending_penalty_percentage = 0;
+    sla.penalty_decrease_effective_at = 0;
+    sla.created_at = Clock::get()?.unix_timestamp;
+    sla.last_sla_update_at = sla.created_at;
+    sla.is_active = true;
+    sla.deactivated_at = 0;
+    sla.locked_until = locked_until;
+    sla.bump = bump;
+
+    msg!("SLA defined: {}% uptime, {}ms response, {}% accuracy",
+         uptime_guarantee, max_response_time_ms, accuracy_guarantee);
+    Ok(())
+}
+
+/// Region codes must be two printable, non-space ASCII characters
+pub fn is_valid_region(region: &[u8; 2]) -> bool {
+    region.iter().all(|b| b.is_ascii_graphic())
+}
+
+// Account Structures
+
+#[account]
+pub struct Protocol {
+    pub authority: Pubkey,
+    pub total_providers: u64,
+    pub total_staked: u64,
+    pub total_slashed: u64,
+    pub bump: u8,
+    pub stake_warning_threshold_bps: u16,
+    pub violation_weights: [u16; 5],
+    pub overcollateralization_bonus_cap_bps: u16,
+    pub settlement_discount_bps: u16,
+    pub authorities: Vec<Pubkey>,
+    pub threshold: u8,
+    pub min_reputation_to_serve: u64,
+    pub evidence_ttl_seconds: i64,
+    pub protected_balance: u64,
+    pub onboarding_shield_seconds: i64,
+    pub max_description_len: u16,
+    pub first_reporter_bonus_bps: u16,
+    pub self_dealing_checks: bool,
+    pub violation_decay_seconds: i64,
+    pub min_successes_for_sla: u64,
+    pub evidence_uri_required: [bool; 5],
+    pub paused: bool,
+    pub circuit_breaker_threshold: u64,
+    pub slashed_in_current_window: u64,
+    pub window_start: i64,
+    pub config_manager: Pubkey,
+    pub report_fee: u64,
+    pub season: u32,
+    pub season_start: i64,
+    pub season_carryover_bps: u16,
+    pub slashing_frozen: bool,
+    pub crank_reward: u64,
+    pub crank_budget: u64,
+    pub crank_cooldown_seconds: i64,
+    pub unique_names: bool,
+    pub duration_penalty_bps_per_hour: u16,
+    pub max_duration_penalty_bps: u16,
+    pub max_reporter_compensation: u64,
+    pub min_sla_update_interval: i64,
+    pub max_single_slash_bps: u16,
+    pub min_slash_amount: u64,
+    pub onboarding_mode: OnboardingMode,
+    pub cure_window_seconds: i64,
+    pub false_attestation_penalty_bps: u16,
+    pub slashed_rolling: u64,
+    pub rolling_window_start: i64,
+    pub rolling_window_seconds: i64,
+    pub withdrawal_tier_reputations: [u64; WITHDRAWAL_TIERS],
+    pub max_withdrawal_per_period: [u64; WITHDRAWAL_TIERS],
+    pub withdrawal_period_seconds: i64,
+    pub honest_pool_bps: u16,
+    pub honest_pool_accrued: u64,
+    pub honest_pool_weight: u64,
+    pub honest_payout: u64,
+    pub honest_payout_weight: u64,
+    pub honest_payout_remaining: u64,
+    pub dismissal_refund_bps: u16,
+    pub cure_window_by_type: [i64; 5],
+    pub cure_escalation_threshold: u64,
+    pub min_distinct_reporters: u8,
+}
+
+impl Protocol {
+    /// Add a slash to the current circuit-breaker window, starting a new window once the
+    /// old one has run its course, and pause the protocol if the window's volume is too high
+    pub fn record_slash_volume(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now.saturating_sub(self.window_start) >= CIRCUIT_BREAKER_WINDOW_SECONDS {
+            self.window_start = now;
+            self.slashed_in_current_window = 0;
+        }
+        self.slashed_in_current_window = self
+            .slashed_in_current_window
+            .checked_add(amount)
+            .ok_or(error!(CovenantError::MathOverflow))?;
+
+        if self.circuit_breaker_threshold > 0
+            && self.slashed_in_current_window > self.circuit_breaker_threshold
+            && !self.paused
+        {
+            self.paused = true;
+            emit!(CircuitBreakerTripped {
+                slashed_in_window: self.slashed_in_current_window,
+                threshold: self.circuit_breaker_threshold,
+                window_start: self.window_start,
+            });
+        }
+        Ok(())
+    }
+
+    /// Per-period withdrawal limit for a provider with this reputation (0 means unlimited):
+    /// the limit of the highest tier whose minimum reputation it reaches. Reputations below
+    /// every tier get the lowest tier's limit.
+    pub fn withdrawal_limit(&self, reputation: u64) -> u64 {
+        let tier = self
+            .withdrawal_tier_reputations
+            .iter()
+            .rposition(|min_reputation| reputation >= *min_reputation)
+            .unwrap_or(0);
+        self.max_withdrawal_per_period[tier]
+    }
+
+    /// Cure window for a violation type: its own window if one is set, else `cure_window_seconds`
+    pub fn cure_window_for(&self, violation_type: ViolationType) -> i64 {
+        match self.cure_window_by_type[violation_type as usize] {
+            0 => self.cure_window_seconds,
+            window => window,
+        }
+    }
+
+    /// Recent slashing as of `now`: `slashed_rolling` halved once per full rolling window
+    /// elapsed since it was last updated
+    pub fn current_slashed_rolling(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.rolling_window_start) / self.rolling_window_seconds.max(1);
+        self.slashed_rolling.checked_shr(elapsed.clamp(0, 64) as u32).unwrap_or(0)
+    }
+
+    /// Decay recent slashing up to `now`, then add a new slash to it
+    pub fn record_rolling_slash(&mut self, amount: u64, now: i64) {
+        let window = self.rolling_window_seconds.max(1);
+        let elapsed = now.saturating_sub(self.rolling_window_start) / window;
+        self.slashed_rolling = self.current_slashed_rolling(now).saturating_add(amount);
+        self.rolling_window_start = self
+            .rolling_window_start
+            .saturating_add(elapsed.saturating_mul(window));
+    }
+
+    /// Check that admin instructions are approved: by `authority` in single-key mode,
+    /// or by `threshold` of `authorities` signing (the submitter plus signer remaining accounts)
+    pub fn require_admin(&self, signer: &Signer, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        if self.threshold == 0 {
+            require_keys_eq!(signer.key(), self.authority, CovenantError::Unauthorized);
+            return Ok(());
+        }
+
+        let signers: Vec<Pubkey> = std::iter::once(signer.key())
+            .chain(
+                remaining_accounts
+                    .iter()
+                    .filter(|account| account.is_signer)
+                    .map(|account| account.key()),
+            )
+            .collect();
+        require!(
+            verify_multisig(&signers, &self.authorities, self.threshold),
+            CovenantError::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Like `require_admin`, but also accepts the delegated config manager
+    pub fn require_config_editor(&self, signer: &Signer, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        if self.config_manager != Pubkey::default() && signer.key() == self.config_manager {
+            return Ok(());
+        }
+        self.require_admin(signer, remaining_accounts)
+    }
+}
+
+#[account]
+pub struct Provider {
+    pub authority: Pubkey,
+    pub name: String,
+    pub service_endpoint: String,
+    pub stake_amount: u64,
+    pub violations: u64,
+    pub successful_requests: u64,
+    pub created_at: i64,
+    pub status: ProviderStatus,
+    pub bump: u8,
+    pub stake_low_warned: bool,
+    pub violation_counts: [u64; 5],
+    pub reputation_score: u64,
+    pub withdrawals_frozen: bool,
+    pub region: [u8; 2],
+    pub pending_violations: u64,
+    pub overcollateralization_bonus_bps: u16,
+    pub recent_request_ids: [[u8; 16]; RECENT_REQUEST_IDS],
+    pub recent_request_cursor: u8,
+    pub parent: Option<Pubkey>,
+    pub sub_provider_count: u32,
+    pub funding_source: Pubkey,
+    pub auto_topup_target: u64,
+    pub endpoint_version: u16,
+    pub confirmed_violations: u64,
+    pub dismissed_violations: u64,
+    pub vesting: VestingSchedule,
+    pub capabilities: Vec<Capability>,
+    pub seen_violation_types: u8,
+    pub last_violation_at: i64,
+    pub org: Option<Pubkey>,
+    pub org_synced_stake: u64,
+    pub org_synced_violations: u64,
+    pub webhook_hash: [u8; 32],
+    pub imported: bool,
+    pub complaints: u64,
+    pub open_complaints: u64,
+    pub season: u32,
+    pub season_successes: u64,
+    pub season_violation_counts: [u64; 5],
+    pub withdrawn_this_period: u64,
+    pub period_start: i64,
+    pub pre_ban_status: ProviderStatus,
+    pub recent_violations: [ViolationStamp; RECENT_VIOLATIONS],
+    pub recent_violation_cursor: u8,
+    pub topup_armed: bool,
+    pub attestations: u32,
+    pub sponsorships: u32,
+    pub honest_season: u32,
+    pub honest_weight: u64,
+    pub disputes_lost: u64,
+    pub distinct_reporters: [Pubkey; MAX_TRACKED_REPORTERS],
+    pub distinct_reporter_count: u8,
+}
+
+impl Provider {
+    /// Whether the provider is currently serving (active or on probation)
+    pub fn is_serving(&self) -> bool {
+        matches!(self.status, ProviderStatus::Active | ProviderStatus::Probation)
+    }
+
+    /// Whether the provider is still inside the protocol's onboarding shield, which defers slashes
+    pub fn onboarding_shielded(&self, protocol: &Protocol, now: i64) -> bool {
+        now - self.created_at < protocol.onboarding_shield_seconds
+    }
+
+    /// Remember a reporter key that has filed against this provider, up to
+    /// `MAX_TRACKED_REPORTERS` distinct keys
+    pub fn note_reporter(&mut self, reporter: Pubkey) {
+        let count = self.distinct_reporter_count as usize;
+        if count < MAX_TRACKED_REPORTERS && !self.distinct_reporters[..count].contains(&reporter) {
+            self.distinct_reporters[count] = reporter;
+            self.distinct_reporter_count += 1;
+        }
+    }
+
+    /// Count a newly reported violation and re-score reputation with the type's weight.
+    ///
+    /// `violations` doubles as the seed index of the next Violation PDA. It only ever
+    /// increases (overflow aborts the transaction), so an index is never reused even after
+    /// its violation account is closed, and a closed violation can't be recreated in place.
+    pub fn record_violation(&mut self, violation_type: ViolationType, protocol: &Protocol) -> Result<bool> {
+        let type_bit = 1u8 << violation_type as u8;
+        let first_of_type = self.seen_violation_types & type_bit == 0;
+        self.seen_violation_types |= type_bit;
+        self.violations += 1;
+        self.pending_violations += 1;
+        self.sync_season(protocol);
+        self.violation_counts[violation_type as usize] += 1;
+        self.season_violation_counts[violation_type as usize] += 1;
+        self.stamp_violation(violation_type, Clock::get()?.unix_timestamp);
+        self.refresh_reputation(protocol)?;
+        Ok(first_of_type)
+    }
+
+    /// Take back what `record_violation` counted for a report that was never confirmed, so an
+    /// expired report leaves the provider's reputation where it was before it was filed.
+    /// Season counters are only touched if the report was filed in the current season; earlier
+    /// seasons' counts have already been carried over.
+    pub fn forget_violation(
+        &mut self,
+        violation_type: ViolationType,
+        reported_at: i64,
+        protocol: &Protocol,
+    ) -> Result<()> {
+        self.sync_season(protocol);
+        let index = violation_type as usize;
+        self.violation_counts[index] = self.violation_counts[index].saturating_sub(1);
+        if reported_at >= protocol.season_start {
+            self.season_violation_counts[index] = self.season_violation_counts[index].saturating_sub(1);
+        }
+        if let Some(stamp) = self
+            .recent_violations
+            .iter_mut()
+            .find(|stamp| stamp.violation_type == violation_type as u8 && stamp.at == reported_at)
+        {
+            *stamp = ViolationStamp::default();
+        }
+        if self.violation_counts[index] == 0 {
+            self.seen_violation_types &= !(1u8 << violation_type as u8);
+        }
+        self.refresh_reputation(protocol)
+    }
+
+    /// Remember when a violation was counted against the provider, overwriting the oldest stamp
+    pub fn stamp_violation(&mut self, violation_type: ViolationType, now: i64) {
+        let cursor = self.recent_violation_cursor as usize;
+        self.recent_violations[cursor] = ViolationStamp {
+            violation_type: violation_type as u8,
+            at: now,
+        };
+        self.recent_violation_cursor = ((cursor + 1) % RECENT_VIOLATIONS) as u8;
+        self.last_violation_at = now;
+    }
+
+    /// Roll the provider's season counters into the protocol's current season, keeping
+    /// `season_carryover_bps` of them for every season that has passed since its last interaction
+    pub fn sync_season(&mut self, protocol: &Protocol) {
+        let elapsed = protocol.season.saturating_sub(self.season);
+        for _ in 0..elapsed.min(MAX_SEASON_CATCH_UP) {
+            self.season_successes = carry_over(self.season_successes, protocol.season_carryover_bps);
+            for count in self.season_violation_counts.iter_mut() {
+                *count = carry_over(*count, protocol.season_carryover_bps);
+            }
+        }
+        if elapsed > MAX_SEASON_CATCH_UP && protocol.season_carryover_bps < 10_000 {
+            self.season_successes = 0;
+            self.season_violation_counts = [0; 5];
+        }
+        self.season = protocol.season;
+    }
+
+    /// Recompute the cached reputation score from the current season's counters, including
+    /// the overcollateralization bonus and the penalty for unresolved complaints.
+    /// Each violation weighs less as it ages through the protocol's decay window.
+    pub fn refresh_reputation(&mut self, protocol: &Protocol) -> Result<()> {
+        self.overcollateralization_bonus_bps = overcollateralization_bonus_bps(
+            self.stake_amount,
+            MIN_STAKE,
+            protocol.overcollateralization_bonus_cap_bps,
+        );
+
+        self.sync_season(protocol);
+        let base = compute_reputation(
+            self.season_successes,
+            &self.season_violation_counts,
+            &protocol.violation_weights,
+            &self.recent_violations,
+            self.last_violation_at,
+            Clock::get()?.unix_timestamp,
+            protocol.violation_decay_seconds,
+        );
+        let multiplier_bps = 10_000 + self.overcollateralization_bonus_bps as u128
+            - complaint_penalty_bps(self.open_complaints) as u128;
+        self.reputation_score = ((base as u128).saturating_mul(multiplier_bps) / 10_000) as u64;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct SLA {
+    pub provider: Pubkey,
+    pub uptime_guarantee: u8,
+    pub max_response_time_ms: u32,
+    pub accuracy_guarantee: u8,
+    pub penalty_percentage: u8,
+    pub created_at: i64,
+    pub is_active: bool,
+    pub bump: u8,
+    pub pending_penalty_percentage: u8,
+    pub penalty_decrease_effective_at: i64,
+    pub last_sla_update_at: i64,
+    pub deactivated_at: i64,
+    pub locked_until: i64,
+}
+
+impl SLA {
+    /// Stamp a change to the SLA's terms, rejecting it while the SLA is locked or within
+    /// `min_interval` of the previous one
+    pub fn record_update(&mut self, now: i64, min_interval: i64) -> Result<()> {
+        self.require_unlocked(now)?;
+        require!(
+            now.saturating_sub(self.last_sla_update_at) >= min_interval,
+            CovenantError::SLAUpdateTooSoon
+        );
+        self.last_sla_update_at = now;
+        Ok(())
+    }
+
+    /// Reject changes before the lock the provider committed to at definition time expires
+    pub fn require_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.locked_until, CovenantError::SLALocked);
+        Ok(())
+    }
+
+    /// Whether these terms were in force at `at`: from creation until the SLA was deactivated.
+    /// A breach inside that window stays reportable after deactivation, so one discovered late
+    /// can still be reported against the terms it broke.
+    pub fn covers(&self, at: i64) -> bool {
+        at >= self.created_at && (self.is_active || at < self.deactivated_at)
+    }
+
+    /// Penalty in force at `now`, counting a scheduled decrease once its notice period has passed
+    pub fn effective_penalty(&self, now: i64) -> u8 {
+        if self.pending_penalty_percentage != 0 && now >= self.penalty_decrease_effective_at {
+            self.pending_penalty_percentage
+        } else {
+            self.penalty_percentage
+        }
+    }
+
+    /// Fold a matured penalty decrease into `penalty_percentage`
+    pub fn settle_penalty(&mut self, now: i64) {
+        self.penalty_percentage = self.effective_penalty(now);
+        if self.penalty_percentage == self.pending_penalty_percentage {
+            self.pending_penalty_percentage = 0;
+            self.penalty_decrease_effective_at = 0;
+        }
+    }
+}
+
+#[account]
+pub struct Violation {
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub violation_type: ViolationType,
     pub evidence_hash: [u8; 32],
     pub description: String,
     pub timestamp: i64,
     pub is_resolved: bool,
     pub bump: u8,
+    pub is_emergency: bool,
+    pub evidence_valid_until: i64,
+    pub first_of_type: bool,
+    pub evidence_uri: String,
+    pub claims: Vec<u8>,
+    pub settled_claims: u8,
+    pub occurred_at: i64,
+    pub ended_at: i64,
+    pub cure_deadline: i64,
+    pub cure_submitted: bool,
+    pub cure_evidence_hash: [u8; 32],
+    pub is_cured: bool,
+    pub rent_payer: Pubkey,
+    /// The SLA the violation was reported under; only that SLA's terms can settle it
+    pub sla: Pubkey,
+    pub report_fee: u64,
+    pub fee_payer: Pubkey,
+}
+
+impl Violation {
+    /// How long the reported breach lasted
+    pub fn duration_seconds(&self) -> i64 {
+        self.ended_at.saturating_sub(self.occurred_at)
+    }
+
+    /// Reject a slash of this violation: already resolved, evidence expired, or the cure
+    /// window still open or a cure awaiting review
+    pub fn require_slashable(&self, now: i64) -> Result<()> {
+        require!(!self.is_resolved, CovenantError::ViolationAlreadyResolved);
+        require!(now <= self.evidence_valid_until, CovenantError::EvidenceExpired);
+        require!(now >= self.cure_deadline, CovenantError::CureWindowOpen);
+        require!(!self.cure_submitted, CovenantError::CurePending);
+        Ok(())
+    }
+
+    /// Bitmask covering every claim; a report without claim codes counts as one claim
+    pub fn all_claims_mask(&self) -> u8 {
+        ((1u16 << self.claims.len().max(1)) - 1) as u8
+    }
+
+    /// Number of claims not yet settled
+    pub fn open_claims(&self) -> usize {
+        (self.all_claims_mask() & !self.settled_claims).count_ones() as usize
+    }
+
+    /// Bitmask of the given open claim codes, rejecting unknown, settled or repeated codes
+    pub fn claims_mask(&self, codes: &[u8]) -> Result<u8> {
+        let mut mask = 0u8;
+        for code in codes {
+            let index = self
+                .claims
+                .iter()
+                .position(|claim| claim == code)
+                .ok_or(CovenantError::InvalidClaim)?;
+            let bit = 1u8 << index;
+            require!((mask | self.settled_claims) & bit == 0, CovenantError::InvalidClaim);
+            mask |= bit;
+        }
+        Ok(mask)
+    }
+
+    /// Share of `amount` attributable to `count` of the report's claims
+    pub fn claims_share(&self, amount: u64, count: usize) -> u64 {
+        (amount as u128 * count as u128 / self.claims.len().max(1) as u128) as u64
+    }
+}
+
+#[account]
+pub struct CrankerState {
+    pub cranker: Pubkey,
+    pub last_crank_at: i64,
+    pub total_earned: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct NameRecord {
+    pub authority: Pubkey,
+    pub provider: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Complaint {
+    pub provider: Pubkey,
+    pub consumer: Pubkey,
+    pub complaint_type: ComplaintType,
+    pub description_hash: [u8; 32],
+    pub timestamp: i64,
+    pub is_resolved: bool,
+    pub bump: u8,
+}
+
+#[account]
+pub struct SelfAttestation {
+    pub provider: Pubkey,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub uptime_percentage: u8,
+    pub response_time_ms: u32,
+    pub accuracy_percentage: u8,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl SelfAttestation {
+    /// Whether the violation happened inside the attested period on a metric the provider
+    /// claimed to be meeting under the SLA
+    pub fn contradicted_by(&self, violation: &Violation, sla: &SLA) -> bool {
+        if violation.occurred_at < self.period_start || violation.occurred_at >= self.period_end {
+            return false;
+        }
+        match violation.violation_type {
+            ViolationType::UptimeViolation | ViolationType::ServiceUnavailable => {
+                self.uptime_percentage >= sla.uptime_guarantee
+            }
+            ViolationType::ResponseTimeViolation => self.response_time_ms <= sla.max_response_time_ms,
+            ViolationType::AccuracyViolation => self.accuracy_percentage >= sla.accuracy_guarantee,
+            ViolationType::Other => false,
+        }
+    }
+}
+
+#[account]
+pub struct ReporterAllowance {
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub funded: u64,
+    pub spent: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct ReporterRecord {
+    pub reporter: Pubkey,
+    pub confirmed_reports: u64,
+    pub dismissed_reports: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Organization {
+    pub authority: Pubkey,
+    pub name: String,
+    pub member_count: u32,
+    pub total_stake: u64,
+    pub total_violations: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct MaintenanceWindow {
+    pub provider: Pubkey,
+    pub windows: Vec<MaintenancePeriod>,
+    pub bump: u8,
+}
+
+impl MaintenanceWindow {
+    pub fn is_active_at(&self, now: i64) -> bool {
+        self.windows
+            .iter()
+            .any(|window| window.start <= now && now < window.end)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MaintenancePeriod {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// A typed provider attribute; see the CAPABILITY_* constants for well-known keys
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Capability {
+    pub key: u8,
+    pub value: u64,
+}
+
+/// When a violation was counted against a provider, for time-weighted reputation decay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ViolationStamp {
+    pub violation_type: u8,
+    pub at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct VestingSchedule {
+    pub amount: u64,
+    pub start: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+}
+
+impl VestingSchedule {
+    /// Amount of the scheduled stake still locked at `now`
+    pub fn locked_at(&self, now: i64) -> u64 {
+        self.amount
+            - vested_amount(self.amount, self.start, self.cliff_seconds, self.duration_seconds, now)
+    }
+}
+
+/// Historical provider record carried over by `import_provider_snapshot`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProviderSnapshot {
+    pub name: String,
+    pub service_endpoint: String,
+    pub endpoint_version: u16,
+    pub region: [u8; 2],
+    pub created_at: i64,
+    pub successful_requests: u64,
+    pub violations: u64,
+    pub violation_counts: [u64; 5],
+    pub last_violation_at: i64,
+}
+
+/// Lamport breakdown returned by `estimate_onboarding_cost`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OnboardingCost {
+    pub stake_amount: u64,
+    pub provider_rent: u64,
+    pub vault_rent: u64,
+    pub sla_rent: u64,
+    pub name_record_rent: u64,
+    pub total: u64,
+}
+
+/// Protocol-wide snapshot returned by `get_protocol_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ProtocolStats {
+    pub total_providers: u64,
+    pub total_staked: u64,
+    pub total_slashed: u64,
+    pub slashed_rolling: u64,
+    pub slash_rate_bps: u64,
+    pub average_stake: u64,
+    pub paused: bool,
+}
+
+/// One provider's health snapshot, returned by `provider_health`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProviderHealth {
+    pub provider: Pubkey,
+    pub status: ProviderStatus,
+    pub region: [u8; 2],
+    pub endpoint_version: u16,
+    pub stake_amount: u64,
+    pub reputation_score: u64,
+    pub overcollateralization_bonus_bps: u16,
+    pub pending_violations: u64,
+    pub confirmed_violations: u64,
+    pub dismissed_violations: u64,
+    pub capabilities: Vec<Capability>,
+    pub in_maintenance: bool,
+    pub season: u32,
+    pub season_successes: u64,
+    pub season_violation_counts: [u64; 5],
+    pub successful_requests: u64,
+    pub violation_counts: [u64; 5],
+    pub sla_locked_until: Option<i64>,
+}
+
+/// Key terms of one SLA, returned by `get_slas`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SLASummary {
+    pub sla: Pubkey,
+    pub provider: Pubkey,
+    pub uptime_guarantee: u8,
+    pub max_response_time_ms: u32,
+    pub accuracy_guarantee: u8,
+    pub penalty_percentage: u8,
+    pub is_active: bool,
+    pub locked_until: i64,
+}
+
+/// Program addresses belonging to one provider authority, returned by `derive_addresses`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DerivedAddresses {
+    pub protocol: Pubkey,
+    pub provider: Pubkey,
+    pub stake_vault: Pubkey,
+    pub sla: Pubkey,
+}
+
+/// Provider lifecycle. A slash that leaves at least the minimum stake moves an active
+/// provider to `Probation`: it keeps serving and can rebuild by recording successes, but
+/// can't define new SLAs until its reputation clears the protocol floor. In approval
+/// onboarding mode, new providers wait in `PendingApproval` until the authority decides.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderStatus {
+    Active,
+    Probation,
+    Inactive,
+    Banned,
+    PendingApproval,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingMode {
+    Open,
+    Approval,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationType {
+    UptimeViolation,
+    ResponseTimeViolation,
+    AccuracyViolation,
+    ServiceUnavailable,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ComplaintType {
+    Quality,
+    Latency,
+    Support,
+    Billing,
+    Other,
+}
+
+// Events
+
+#[event]
+pub struct StakeLow {
+    pub provider: Pubkey,
+    pub stake_amount: u64,
+    pub min_stake: u64,
+}
+
+#[event]
+pub struct ProviderBlacklisted {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProviderUnblacklisted {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProviderWithdrawalsFrozen {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProviderWithdrawalsUnfrozen {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RegionUpdated {
+    pub provider: Pubkey,
+    pub region: [u8; 2],
+}
+
+#[event]
+pub struct WebhookUpdated {
+    pub provider: Pubkey,
+    pub webhook_hash: [u8; 32],
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub slashed_in_window: u64,
+    pub threshold: u64,
+    pub window_start: i64,
+}
+
+#[event]
+pub struct ProviderImported {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+    pub successful_requests: u64,
+    pub violations: u64,
+}
+
+#[event]
+pub struct EndpointUpdated {
+    pub provider: Pubkey,
+    pub endpoint_version: u16,
+}
+
+#[event]
+pub struct StakeToppedUp {
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub stake_amount: u64,
+}
+
+#[event]
+pub struct FundingWithdrawn {
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct TreasurySwept {
+    pub providers: u32,
+    pub total: u64,
+}
+
+#[event]
+pub struct HonestProviderEnrolled {
+    pub provider: Pubkey,
+    pub season: u32,
+    pub weight: u64,
+}
+
+#[event]
+pub struct HonestRewardClaimed {
+    pub provider: Pubkey,
+    pub season: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ViolationSettled {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProviderMetricsRefreshed {
+    pub provider: Pubkey,
+    pub reputation_score: u64,
+    pub overcollateralization_bonus_bps: u16,
+}
+
+#[event]
+pub struct ViolationReported {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub violation_type: ViolationType,
+    pub report_fee: u64,
+}
+
+#[event]
+pub struct EmergencyViolationReported {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub violation_type: ViolationType,
+}
+
+#[event]
+pub struct MultisigUpdated {
+    pub authorities: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProbationGranted {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProbationEnded {
+    pub provider: Pubkey,
+}
+
+#[event]
+pub struct ComplaintFiled {
+    pub complaint: Pubkey,
+    pub provider: Pubkey,
+    pub consumer: Pubkey,
+    pub complaint_type: ComplaintType,
+}
+
+#[event]
+pub struct ComplaintResolved {
+    pub complaint: Pubkey,
+    pub provider: Pubkey,
+}
+
+#[event]
+pub struct SelfAttested {
+    pub attestation: Pubkey,
+    pub provider: Pubkey,
+    pub period_start: i64,
+    pub period_end: i64,
+}
+
+#[event]
+pub struct AttestationContradicted {
+    pub attestation: Pubkey,
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub extra_penalty: u64,
+}
+
+#[event]
+pub struct ReporterSponsored {
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeasonAdvanced {
+    pub season: u32,
+    pub season_start: i64,
+}
+
+#[event]
+pub struct VaultDiscrepancy {
+    pub provider: Pubkey,
+    pub recorded_stake: u64,
+    pub vault_balance: u64,
+    pub reconciled: bool,
+}
+
+#[event]
+pub struct CrankRewardPaid {
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub remaining_budget: u64,
+}
+
+#[event]
+pub struct NameReleased {
+    pub authority: Pubkey,
+    pub provider: Pubkey,
+}
+
+#[event]
+pub struct SlashClamped {
+    pub provider: Pubkey,
+    pub requested: u64,
+    pub slashed: u64,
+}
+
+#[event]
+pub struct ProviderApproved {
+    pub provider: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProviderRejected {
+    pub provider: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct CureSubmitted {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub remediation_hash: [u8; 32],
+}
+
+#[event]
+pub struct CureReviewed {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub accepted: bool,
+}
+
+#[event]
+pub struct ViolationExpired {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+}
+
+#[event]
+pub struct ViolationDismissed {
+    pub violation: Pubkey,
+    pub provider: Pubkey,
+    pub reporter: Pubkey,
+    pub malicious: bool,
+    pub refunded: u64,
+    pub forfeited: u64,
+}
+
+#[event]
+pub struct ProtectedBalanceReached {
+    pub provider: Pubkey,
+    pub requested: u64,
+    pub slashed: u64,
+}
+
+#[event]
+pub struct PenaltyIncreased {
+    pub sla: Pubkey,
+    pub penalty_percentage: u8,
+}
+
+#[event]
+pub struct PenaltyDecreaseScheduled {
+    pub sla: Pubkey,
+    pub penalty_percentage: u8,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct SLADeactivated {
+    pub sla: Pubkey,
+    pub deactivated_at: i64,
+}
+
+#[event]
+pub struct MaintenanceDeclared {
+    pub provider: Pubkey,
+    pub start: i64,
+    pub end: i64,
+}
+
+// Instruction Contexts
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 1 + 2 + 2 * 5 + 2 + 2 + 4 + 32 * MAX_AUTHORITIES + 1 + 8 + 8 + 8 + 8 + 2 + 2 + 1 + 8 + 8 + 5 + 1 + 8 + 8 + 8 + 32 + 8 + 4 + 8 + 2 + 1 + 8 + 8 + 8 + 1 + 2 + 2 + 8 + 8 + 2 + 8 + 1 + 8 + 2 + 8 + 8 + 8 + 8 * WITHDRAWAL_TIERS * 2 + 8 + 2 + 8 * 5 + 2 + 8 * 5 + 8 + 1,
+        seeds = [b"protocol"],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Protocol treasury collecting fees
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct RegisterProvider<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = provider_authority,
+        space = PROVIDER_SPACE,
+        seeds = [b"provider", provider_authority.key().as_ref()],
+        bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// CHECK: Vault PDA to hold staked funds
+    #[account(
+        mut,
+        seeds = [b"vault", provider_authority.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub provider_authority: Signer<'info>,
+
+    /// CHECK: Name registry PDA, created in the handler; required while unique names are enforced
+    #[account(
+        mut,
+        seeds = [b"name", &name_key(&name)[..32], &name_key(&name)[32..]],
+        bump
+    )]
+    pub name_record: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct ReleaseName<'info> {
+    #[account(
+        mut,
+        seeds = [b"name", &name_key(&name)[..32], &name_key(&name)[32..]],
+        bump = name_record.bump,
+        has_one = authority @ CovenantError::Unauthorized,
+        close = authority
+    )]
+    pub name_record: Account<'info, NameRecord>,
+
+    /// CHECK: The provider that claimed the name; only its address and emptiness are read
+    #[account(
+        address = name_record.provider,
+        constraint = provider.data_is_empty() @ CovenantError::ProviderStillRegistered
+    )]
+    pub provider: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterWithSLA<'info> {
+    pub register: RegisterProvider<'info>,
+
+    #[account(
+        init,
+        payer = register.provider_authority,
+        space = SLA_SPACE,
+        seeds = [b"sla", register.provider.key().as_ref()],
+        bump
+    )]
+    pub sla: Account<'info, SLA>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DefineSLA<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SLA_SPACE,
+        seeds = [b"sla", provider.key().as_ref()],
+        bump
+    )]
+    pub sla: Account<'info, SLA>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSLA<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"sla", provider.key().as_ref()],
+        bump = sla.bump,
+        constraint = sla.is_active @ CovenantError::NoActiveSLA
+    )]
+    pub sla: Account<'info, SLA>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportViolation<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// The provider's SLA, or its parent's for a sub-provider
+    #[account(
+        seeds = [b"sla", sla.provider.as_ref()],
+        bump = sla.bump,
+        constraint = sla.provider == provider.key() || Some(sla.provider) == provider.parent @ CovenantError::SLAMismatch
+    )]
+    pub sla: Account<'info, SLA>,
+
+    /// CHECK: The provider's maintenance schedule PDA, read only if it has been created
+    #[account(
+        seeds = [b"maintenance", provider.key().as_ref()],
+        bump
+    )]
+    pub maintenance: UncheckedAccount<'info>,
+
+    /// CHECK: The new violation PDA, created in the handler so the allowance can fund it
+    #[account(
+        mut,
+        seeds = [b"violation", provider.key().as_ref(), &provider.violations.to_le_bytes()],
+        bump
+    )]
+    pub violation: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Optional account funding the violation account's rent; defaults to the reporter
+    #[account(mut)]
+    pub rent_sponsor: Option<Signer<'info>>,
+
+    /// The provider's allowance for this reporter, which pays the report's rent and fee
+    #[account(
+        mut,
+        seeds = [b"allowance", provider.key().as_ref(), reporter.key().as_ref()],
+        bump = reporter_allowance.bump
+    )]
+    pub reporter_allowance: Option<Account<'info, ReporterAllowance>>,
+
+    /// The reporter's track record, created with its first report
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor.as_ref().unwrap_or(&reporter),
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"reporter", reporter.key().as_ref()],
+        bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reporter: Pubkey)]
+pub struct SponsorReporter<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"allowance", provider.key().as_ref(), reporter.as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, ReporterAllowance>,
+
+    /// The reporter's track record, opened here so a sponsored reporter never pays for it
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"reporter", reporter.as_ref()],
+        bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSponsorship<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"allowance", provider.key().as_ref(), allowance.reporter.as_ref()],
+        bump = allowance.bump,
+        has_one = provider,
+        close = authority
+    )]
+    pub allowance: Account<'info, ReporterAllowance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyReport<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// The provider's SLA, or its parent's for a sub-provider
+    #[account(
+        seeds = [b"sla", sla.provider.as_ref()],
+        bump = sla.bump,
+        constraint = sla.provider == provider.key() || Some(sla.provider) == provider.parent @ CovenantError::SLAMismatch
+    )]
+    pub sla: Account<'info, SLA>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VIOLATION_SPACE,
+        seeds = [b"violation", provider.key().as_ref(), &provider.violations.to_le_bytes()],
+        bump
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// The authority's track record as a reporter, created with its first report
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"reporter", authority.key().as_ref()],
+        bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Slash<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// The provider's own SLA, or its parent's SLA for sub-providers
+    #[account(
+        seeds = [b"sla", sla.provider.as_ref()],
+        bump = sla.bump,
+        constraint = sla.provider == provider.key() || Some(sla.provider) == provider.parent @ CovenantError::SLAMismatch
+    )]
+    pub sla: Account<'info, SLA>,
+
+    #[account(
+        mut,
+        has_one = provider,
+        has_one = reporter,
+        has_one = sla @ CovenantError::SLAMismatch
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// CHECK: Vault PDA holding staked funds. Always the violating provider's own vault, even
+    /// when it is slashed under its parent's SLA; a parent's stake is never drawn on.
+    #[account(
+        mut,
+        seeds = [b"vault", provider.authority.as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    /// The reporter's track record
+    #[account(
+        mut,
+        seeds = [b"reporter", reporter.key().as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Receives the honest-provider pool's share of the slash
+    #[account(
+        mut,
+        seeds = [b"honest_pool"],
+        bump
+    )]
+    pub honest_pool: SystemAccount<'info>,
+
+    /// Required when the provider is a sub-provider
+    #[account(
+        mut,
+        seeds = [b"provider", parent_provider.authority.as_ref()],
+        bump = parent_provider.bump
+    )]
+    pub parent_provider: Option<Account<'info, Provider>>,
+
+    /// The provider's self-attestation for the period the violation occurred in, if any
+    #[account(
+        constraint = attestation.provider == provider.key() @ CovenantError::AttestationMismatch
+    )]
+    pub attestation: Option<Account<'info, SelfAttestation>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcknowledgeViolation<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// The provider's own SLA, or its parent's SLA for sub-providers
+    #[account(
+        seeds = [b"sla", sla.provider.as_ref()],
+        bump = sla.bump,
+        constraint = sla.provider == provider.key() || Some(sla.provider) == provider.parent @ CovenantError::SLAMismatch
+    )]
+    pub sla: Account<'info, SLA>,
+
+    #[account(
+        mut,
+        has_one = provider,
+        has_one = reporter,
+        has_one = sla @ CovenantError::SLAMismatch
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// CHECK: Vault PDA holding staked funds
+    #[account(
+        mut,
+        seeds = [b"vault", provider.authority.as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub reporter: SystemAccount<'info>,
+
+    /// The reporter's track record
+    #[account(
+        mut,
+        seeds = [b"reporter", reporter.key().as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Receives the honest-provider pool's share of the slash
+    #[account(
+        mut,
+        seeds = [b"honest_pool"],
+        bump
+    )]
+    pub honest_pool: SystemAccount<'info>,
+
+    /// Required when the provider is a sub-provider
+    #[account(
+        mut,
+        seeds = [b"provider", parent_provider.authority.as_ref()],
+        bump = parent_provider.bump
+    )]
+    pub parent_provider: Option<Account<'info, Provider>>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CureViolation<'info> {
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        has_one = provider
+    )]
+    pub violation: Account<'info, Violation>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewCure<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        has_one = provider
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// The reporter's track record
+    #[account(
+        mut,
+        seeds = [b"reporter", violation.reporter.as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireViolation<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        has_one = provider,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// Whoever bore the violation account's rent, refunded on close
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+
+    /// The reporter's track record
+    #[account(
+        mut,
+        seeds = [b"reporter", violation.reporter.as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"cranker", cranker.key().as_ref()],
+        bump
+    )]
+    pub cranker_state: Account<'info, CrankerState>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DismissViolation<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        has_one = provider,
+        has_one = rent_payer,
+        has_one = fee_payer,
+        close = rent_payer
+    )]
+    pub violation: Account<'info, Violation>,
+
+    /// Whoever bore the violation account's rent, refunded on close
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+
+    /// Whoever paid the report fee, refunded its share
+    #[account(mut)]
+    pub fee_payer: SystemAccount<'info>,
+
+    /// The reporter's track record
+    #[account(
+        mut,
+        seeds = [b"reporter", violation.reporter.as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FileComplaint<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + 32 + 32 + 1 + 32 + 8 + 1 + 1,
+        seeds = [b"complaint", provider.key().as_ref(), &provider.complaints.to_le_bytes()],
+        bump
+    )]
+    pub complaint: Account<'info, Complaint>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(period_start: i64)]
+pub struct SelfAttest<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 1 + 4 + 1 + 8 + 1,
+        seeds = [b"attestation", provider.key().as_ref(), &period_start.to_le_bytes()],
+        bump
+    )]
+    pub attestation: Account<'info, SelfAttestation>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveComplaint<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        has_one = provider
+    )]
+    pub complaint: Account<'info, Complaint>,
+
+    pub authority: Signer<'info>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum ViolationType {
-    UptimeViolation,
-    ResponseTimeViolation,
-    AccuracyViolation,
-    ServiceUnavailable,
-    Other,
+#[derive(Accounts)]
+pub struct RecordSuccess<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshProviderMetrics<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+}
+
+#[derive(Accounts)]
+pub struct AuditVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// CHECK: Vault PDA holding staked funds
+    #[account(
+        seeds = [b"vault", provider.authority.as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    /// Anyone can audit; only the protocol authority can reconcile
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider_authority.key().as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// CHECK: Vault PDA holding staked funds
+    #[account(
+        mut,
+        seeds = [b"vault", provider_authority.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    /// The provider's funding PDA, emptied back to the authority when the provider is closed
+    #[account(
+        mut,
+        seeds = [b"funding", provider.key().as_ref()],
+        bump
+    )]
+    pub funding_source: SystemAccount<'info>,
+
+    /// CHECK: The provider's SLA PDA, closed with the provider if it was ever defined
+    #[account(
+        mut,
+        seeds = [b"sla", provider.key().as_ref()],
+        bump
+    )]
+    pub sla: UncheckedAccount<'info>,
+
+    /// CHECK: The provider's maintenance schedule PDA, closed with the provider if it exists
+    #[account(
+        mut,
+        seeds = [b"maintenance", provider.key().as_ref()],
+        bump
+    )]
+    pub maintenance: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub provider_authority: Signer<'info>,
+
+    /// CHECK: Provider authority for validation
+    pub authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EstimateOnboardingCost<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+#[derive(Accounts)]
+pub struct GetProtocolStats<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+#[derive(Accounts)]
+pub struct GetProviderHealth<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// CHECK: The provider's maintenance schedule PDA, read only if it has been created
+    #[account(
+        seeds = [b"maintenance", provider.key().as_ref()],
+        bump
+    )]
+    pub maintenance: UncheckedAccount<'info>,
+
+    /// The provider's own SLA, to report its lock
+    #[account(
+        seeds = [b"sla", provider.key().as_ref()],
+        bump = sla.bump
+    )]
+    pub sla: Option<Account<'info, SLA>>,
+}
+
+#[derive(Accounts)]
+pub struct GetSlas<'info> {
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+}
+
+#[derive(Accounts)]
+pub struct MaxRecoverable<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// The provider's SLA, or its parent's for a sub-provider
+    #[account(
+        seeds = [b"sla", sla.provider.as_ref()],
+        bump = sla.bump,
+        constraint = sla.provider == provider.key() || Some(sla.provider) == provider.parent @ CovenantError::SLAMismatch
+    )]
+    pub sla: Account<'info, SLA>,
+
+    /// A specific report to price, otherwise a new report is priced
+    #[account(has_one = provider, has_one = sla @ CovenantError::SLAMismatch)]
+    pub violation: Option<Account<'info, Violation>>,
+
+    /// The provider's self-attestation for the period the violation occurred in, if any
+    #[account(
+        constraint = attestation.provider == provider.key() @ CovenantError::AttestationMismatch
+    )]
+    pub attestation: Option<Account<'info, SelfAttestation>>,
+}
+
+#[derive(Accounts)]
+pub struct ReporterAccuracy<'info> {
+    #[account(
+        seeds = [b"reporter", reporter_record.reporter.as_ref()],
+        bump = reporter_record.bump
+    )]
+    pub reporter_record: Account<'info, ReporterRecord>,
+}
+
+#[derive(Accounts)]
+pub struct DeriveAddresses {}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(provider_authority: Pubkey, snapshot: ProviderSnapshot)]
+pub struct ImportProviderSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PROVIDER_SPACE,
+        seeds = [b"provider", provider_authority.as_ref()],
+        bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    /// CHECK: Vault PDA that will hold the imported provider's stake
+    #[account(
+        mut,
+        seeds = [b"vault", provider_authority.as_ref()],
+        bump
+    )]
+    pub stake_vault: AccountInfo<'info>,
+
+    /// CHECK: Name registry PDA, created in the handler; required while unique names are enforced
+    #[account(
+        mut,
+        seeds = [b"name", &name_key(&snapshot.name)[..32], &name_key(&snapshot.name)[32..]],
+        bump
+    )]
+    pub name_record: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-// Instruction Contexts
+#[derive(Accounts)]
+pub struct SetBlacklist<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    pub authority: Signer<'info>,
+}
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct ApproveProvider<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 8 + 8 + 8 + 1,
         seeds = [b"protocol"],
-        bump
+        bump = protocol.bump
     )]
     pub protocol: Account<'info, Protocol>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
 
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String)]
-pub struct RegisterProvider<'info> {
+pub struct RejectProvider<'info> {
     #[account(
         mut,
         seeds = [b"protocol"],
@@ -319,15 +5692,14 @@ pub struct RegisterProvider<'info> {
     pub protocol: Account<'info, Protocol>,
 
     #[account(
-        init,
-        payer = provider_authority,
-        space = 8 + 32 + 4 + 64 + 4 + 256 + 8 + 8 + 8 + 8 + 1 + 1,
+        mut,
         seeds = [b"provider", provider_authority.key().as_ref()],
-        bump
+        bump = provider.bump,
+        close = provider_authority
     )]
     pub provider: Account<'info, Provider>,
 
-    /// CHECK: Vault PDA to hold staked funds
+    /// CHECK: Vault PDA holding staked funds
     #[account(
         mut,
         seeds = [b"vault", provider_authority.key().as_ref()],
@@ -335,14 +5707,60 @@ pub struct RegisterProvider<'info> {
     )]
     pub stake_vault: AccountInfo<'info>,
 
+    /// The provider's funding PDA, emptied back to the authority when the provider is rejected
+    #[account(
+        mut,
+        seeds = [b"funding", provider.key().as_ref()],
+        bump
+    )]
+    pub funding_source: SystemAccount<'info>,
+
     #[account(mut)]
-    pub provider_authority: Signer<'info>,
+    pub provider_authority: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct DefineSLA<'info> {
+pub struct GrantProbation<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalFreeze<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump
+    )]
+    pub provider: Account<'info, Provider>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProvider<'info> {
     #[account(
         mut,
         seeds = [b"provider", provider.authority.as_ref()],
@@ -351,14 +5769,32 @@ pub struct DefineSLA<'info> {
     )]
     pub provider: Account<'info, Provider>,
 
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeclareMaintenance<'info> {
     #[account(
-        init,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        init_if_needed,
         payer = authority,
-        space = 8 + 32 + 1 + 4 + 1 + 1 + 8 + 1 + 1,
-        seeds = [b"sla", provider.key().as_ref()],
+        space = 8 + 32 + 4 + 16 * MAX_MAINTENANCE_WINDOWS + 1,
+        seeds = [b"maintenance", provider.key().as_ref()],
         bump
     )]
-    pub sla: Account<'info, SLA>,
+    pub maintenance: Account<'info, MaintenanceWindow>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -367,31 +5803,128 @@ pub struct DefineSLA<'info> {
 }
 
 #[derive(Accounts)]
-pub struct ReportViolation<'info> {
+pub struct SetParentProvider<'info> {
     #[account(
         mut,
         seeds = [b"provider", provider.authority.as_ref()],
-        bump = provider.bump
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", parent_authority.key().as_ref()],
+        bump = parent_provider.bump
+    )]
+    pub parent_provider: Account<'info, Provider>,
+
+    pub authority: Signer<'info>,
+
+    pub parent_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClearParentProvider<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized,
+        constraint = provider.parent == Some(parent_provider.key()) @ CovenantError::InvalidParent
     )]
     pub provider: Account<'info, Provider>,
 
+    #[account(
+        mut,
+        seeds = [b"provider", parent_provider.authority.as_ref()],
+        bump = parent_provider.bump
+    )]
+    pub parent_provider: Account<'info, Provider>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOrganization<'info> {
     #[account(
         init,
-        payer = reporter,
-        space = 8 + 32 + 32 + 1 + 32 + 4 + 512 + 8 + 1 + 1,
-        seeds = [b"violation", provider.key().as_ref(), &provider.violations.to_le_bytes()],
+        payer = authority,
+        space = 8 + 32 + 4 + 64 + 4 + 8 + 8 + 1,
+        seeds = [b"organization", authority.key().as_ref()],
         bump
     )]
-    pub violation: Account<'info, Violation>,
+    pub organization: Account<'info, Organization>,
 
     #[account(mut)]
-    pub reporter: Signer<'info>,
+    pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Slash<'info> {
+pub struct JoinOrganization<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"organization", organization_authority.key().as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Account<'info, Organization>,
+
+    pub authority: Signer<'info>,
+
+    pub organization_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveOrganization<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized,
+        constraint = provider.org == Some(organization.key()) @ CovenantError::NotInOrganization
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"organization", organization.authority.as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Account<'info, Organization>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncOrganizationMember<'info> {
+    #[account(
+        mut,
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        constraint = provider.org == Some(organization.key()) @ CovenantError::NotInOrganization
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"organization", organization.authority.as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct TriggerTopup<'info> {
     #[account(
         mut,
         seeds = [b"protocol"],
@@ -406,19 +5939,13 @@ pub struct Slash<'info> {
     )]
     pub provider: Account<'info, Provider>,
 
-    #[account(
-        seeds = [b"sla", provider.key().as_ref()],
-        bump = sla.bump
-    )]
-    pub sla: Account<'info, SLA>,
-
+    /// Pre-funded PDA the provider authorized for top-ups
     #[account(
         mut,
-        seeds = [b"violation", provider.key().as_ref(), &(provider.violations - 1).to_le_bytes()],
-        bump = violation.bump,
-        has_one = reporter
+        seeds = [b"funding", provider.key().as_ref()],
+        bump
     )]
-    pub violation: Account<'info, Violation>,
+    pub funding_source: SystemAccount<'info>,
 
     /// CHECK: Vault PDA holding staked funds
     #[account(
@@ -428,26 +5955,95 @@ pub struct Slash<'info> {
     )]
     pub stake_vault: AccountInfo<'info>,
 
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFunding<'info> {
+    #[account(
+        seeds = [b"provider", provider.authority.as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
+    )]
+    pub provider: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [b"funding", provider.key().as_ref()],
+        bump
+    )]
+    pub funding_source: SystemAccount<'info>,
+
     #[account(mut)]
-    pub reporter: Signer<'info>,
+    pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct RecordSuccess<'info> {
+pub struct SweepToTreasury<'info> {
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     #[account(
         mut,
-        seeds = [b"provider", provider.authority.as_ref()],
-        bump = provider.bump
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetHonestPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"honest_pool"],
+        bump
+    )]
+    pub honest_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnrollHonestProvider<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"provider", authority.key().as_ref()],
+        bump = provider.bump,
+        has_one = authority @ CovenantError::Unauthorized
     )]
     pub provider: Account<'info, Provider>,
 
-    pub caller: Signer<'info>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawStake<'info> {
+pub struct ClaimHonestReward<'info> {
     #[account(
         mut,
         seeds = [b"protocol"],
@@ -457,25 +6053,21 @@ pub struct WithdrawStake<'info> {
 
     #[account(
         mut,
-        seeds = [b"provider", provider_authority.key().as_ref()],
+        seeds = [b"provider", authority.key().as_ref()],
         bump = provider.bump,
         has_one = authority @ CovenantError::Unauthorized
     )]
     pub provider: Account<'info, Provider>,
 
-    /// CHECK: Vault PDA holding staked funds
     #[account(
         mut,
-        seeds = [b"vault", provider_authority.key().as_ref()],
+        seeds = [b"honest_pool"],
         bump
     )]
-    pub stake_vault: AccountInfo<'info>,
+    pub honest_pool: SystemAccount<'info>,
 
     #[account(mut)]
-    pub provider_authority: Signer<'info>,
-
-    /// CHECK: Provider authority for validation
-    pub authority: AccountInfo<'info>,
+    pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
@@ -488,7 +6080,7 @@ pub enum CovenantError {
     NameTooLong,
     #[msg("Service endpoint exceeds maximum length of 256 characters")]
     EndpointTooLong,
-    #[msg("Description exceeds maximum length of 512 characters")]
+    #[msg("Description exceeds the protocol's maximum length")]
     DescriptionTooLong,
     #[msg("Stake amount is below minimum required")]
     InsufficientStake,
@@ -504,4 +6096,158 @@ pub enum CovenantError {
     NoStakeToSlash,
     #[msg("Withdrawal would breach minimum stake requirement")]
     WouldBreachMinStake,
+    #[msg("Provider is blacklisted")]
+    ProviderBlacklisted,
+    #[msg("Provider withdrawals are frozen pending investigation")]
+    WithdrawalsFrozen,
+    #[msg("Region must be two printable ASCII characters")]
+    InvalidRegion,
+    #[msg("Provider has unresolved violations")]
+    PendingViolations,
+    #[msg("Request id must not be all zeros")]
+    InvalidRequestId,
+    #[msg("Invalid parent provider relationship")]
+    InvalidParent,
+    #[msg("SLA does not belong to the provider or its parent")]
+    SLAMismatch,
+    #[msg("No top-up is needed or the funding source is empty")]
+    TopUpUnavailable,
+    #[msg("Basis points value must be at most 10000")]
+    InvalidBasisPoints,
+    #[msg("Transfer would leave the stake vault below its rent-exempt reserve")]
+    VaultRentReserve,
+    #[msg("Invalid multisig authorities or threshold")]
+    InvalidMultisig,
+    #[msg("Provider reputation is below the protocol minimum")]
+    ReputationTooLow,
+    #[msg("Duration must not be negative")]
+    InvalidDuration,
+    #[msg("Violation evidence has expired")]
+    EvidenceExpired,
+    #[msg("Violation evidence is still valid")]
+    EvidenceStillValid,
+    #[msg("Evidence hash must not be empty")]
+    MissingEvidence,
+    #[msg("Vesting cliff must be between zero and the vesting duration")]
+    InvalidVestingSchedule,
+    #[msg("A vesting schedule is still locking stake")]
+    VestingScheduleActive,
+    #[msg("Withdrawal would release stake that has not vested")]
+    StakeLocked,
+    #[msg("Provider is still within its onboarding shield")]
+    OnboardingShieldActive,
+    #[msg("Provider has no active SLA")]
+    NoActiveSLA,
+    #[msg("Too many capabilities declared")]
+    TooManyCapabilities,
+    #[msg("Capability key declared more than once")]
+    DuplicateCapability,
+    #[msg("Maintenance windows must start in the future and end after they start")]
+    InvalidMaintenanceWindow,
+    #[msg("Too many upcoming maintenance windows")]
+    TooManyMaintenanceWindows,
+    #[msg("Provider is in a declared maintenance window")]
+    InMaintenanceWindow,
+    #[msg("Stake is below the amount the caller expected")]
+    StakeChangedUnexpectedly,
+    #[msg("Reporter is linked to the provider; file through emergency_report")]
+    SelfDealing,
+    #[msg("Provider already belongs to an organization")]
+    AlreadyInOrganization,
+    #[msg("Provider is not a member of this organization")]
+    NotInOrganization,
+    #[msg("Provider needs more successful requests before defining an SLA")]
+    InsufficientTrackRecord,
+    #[msg("This violation type must be reported with an evidence URI")]
+    EvidenceRequired,
+    #[msg("Evidence URI exceeds maximum length of 200 characters")]
+    EvidenceUriTooLong,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Snapshot violation counts don't add up to its violation total")]
+    InvalidSnapshot,
+    #[msg("Provider is on probation")]
+    ProviderOnProbation,
+    #[msg("Complaint already resolved")]
+    ComplaintAlreadyResolved,
+    #[msg("Too many claims in one report")]
+    TooManyClaims,
+    #[msg("Claim code is unknown, repeated or already settled")]
+    InvalidClaim,
+    #[msg("Slashing is frozen")]
+    SlashingFrozen,
+    #[msg("Violation occurrence time is in the future")]
+    InvalidOccurrenceTime,
+    #[msg("Violation occurred outside the SLA's active period")]
+    OutsideSLAWindow,
+    #[msg("Provider name is already taken")]
+    NameTaken,
+    #[msg("Name registry account required while unique names are enforced")]
+    NameRecordRequired,
+    #[msg("Violation ends before it starts")]
+    InvalidViolationWindow,
+    #[msg("SLA terms were updated too recently")]
+    SLAUpdateTooSoon,
+    #[msg("Slash amount is below the protocol minimum")]
+    SlashTooSmall,
+    #[msg("Provider is awaiting onboarding approval")]
+    ProviderPendingApproval,
+    #[msg("Provider is not awaiting onboarding approval")]
+    ProviderNotPendingApproval,
+    #[msg("Cure window must not be negative")]
+    InvalidCureWindow,
+    #[msg("Violation is still within its cure window")]
+    CureWindowOpen,
+    #[msg("Cure window has closed")]
+    CureWindowClosed,
+    #[msg("A cure is awaiting review")]
+    CurePending,
+    #[msg("No cure has been submitted")]
+    NoCureSubmitted,
+    #[msg("Attestation period must be a closed, non-empty past interval")]
+    InvalidAttestationPeriod,
+    #[msg("Attestation belongs to a different provider")]
+    AttestationMismatch,
+    #[msg("Rolling window must be positive")]
+    InvalidRollingWindow,
+    #[msg("Reporter allowance can't cover this report")]
+    InsufficientAllowance,
+    #[msg("Sponsorship amount must be positive")]
+    InvalidAllowanceAmount,
+    #[msg("Withdrawal tiers must have non-decreasing reputations and a positive period")]
+    InvalidWithdrawalTiers,
+    #[msg("Withdrawal exceeds the limit for this provider's reputation")]
+    WithdrawalLimitExceeded,
+    #[msg("Provider has violation or complaint records and can't be closed")]
+    ProviderHasHistory,
+    #[msg("Provider is not blacklisted")]
+    ProviderNotBanned,
+    #[msg("Provider reputation is already at or above the protocol minimum")]
+    ReputationAboveFloor,
+    #[msg("Provider must be closed before its name is released")]
+    ProviderStillRegistered,
+    #[msg("Report fee exceeds the protocol maximum")]
+    ReportFeeTooHigh,
+    #[msg("Funding source holds less than the requested amount")]
+    InsufficientFunding,
+    #[msg("Withdrawal would leave the funding source below its rent-exempt reserve")]
+    FundingRentReserve,
+    #[msg("The provider's self-attestations and sponsorships must be closed with it")]
+    ProviderAccountsOpen,
+    #[msg("Account is not a self-attestation or sponsorship of this provider")]
+    InvalidProviderRecord,
+    #[msg("SLA terms are locked until the time committed to at definition")]
+    SLALocked,
+    #[msg("Sweep source isn't a closed provider's stake vault or funding PDA")]
+    InvalidSweepSource,
+    #[msg("The honest-provider pool is turned off")]
+    HonestPoolDisabled,
+    #[msg("Provider isn't eligible for the honest-provider pool")]
+    HonestRewardIneligible,
+    #[msg("Claim last season's honest-provider reward before enrolling again")]
+    HonestRewardUnclaimed,
+    #[msg("Distinct reporter quorum exceeds the number of reporters tracked per provider")]
+    TooManyDistinctReporters,
 }
@@ -28,6 +28,122 @@ describe("Covenant Protocol", () => {
   // Constants
   const MIN_STAKE = 0.1 * LAMPORTS_PER_SOL; // 0.1 SOL
   const STAKE_AMOUNT = 0.5 * LAMPORTS_PER_SOL; // 0.5 SOL
+  const REPUTATION_SCALE = 10_000;
+
+  // Register a new funded provider and return its keypair and PDAs
+  const registerFreshProvider = async (name: string, stake: number = STAKE_AMOUNT) => {
+    const authority = Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(authority.publicKey, 2 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+
+    const [freshProviderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("provider"), authority.publicKey.toBuffer()],
+      program.programId
+    );
+    const [freshVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), authority.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerProvider(name, `https://${name.toLowerCase()}.ai`, 1, new anchor.BN(stake), Array.from(Buffer.from("US")))
+      .accounts({
+        protocol: protocolPda,
+        provider: freshProviderPda,
+        stakeVault: freshVaultPda,
+        providerAuthority: authority.publicKey,
+        nameRecord: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    return { authority, providerPda: freshProviderPda, vaultPda: freshVaultPda };
+  };
+
+  type FreshProvider = { authority: Keypair; providerPda: PublicKey; vaultPda: PublicKey };
+
+  // Give a fresh provider an SLA and return its PDA
+  const defineSlaFor = async (fresh: FreshProvider, penaltyPercentage = 10, lockedUntil = 0) => {
+    const [freshSlaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sla"), fresh.providerPda.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .defineSla(95, 2000, 99, penaltyPercentage, new anchor.BN(lockedUntil))
+      .accounts({
+        protocol: protocolPda,
+        provider: fresh.providerPda,
+        sla: freshSlaPda,
+        authority: fresh.authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([fresh.authority])
+      .rpc();
+    return freshSlaPda;
+  };
+
+  // File an uptime report against a fresh provider and return the violation PDA
+  const reportAgainst = async (fresh: FreshProvider, freshSlaPda: PublicKey, evidenceByte = 1) => {
+    const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+    const [violation] = PublicKey.findProgramAddressSync(
+      [Buffer.from("violation"), fresh.providerPda.toBuffer(), providerAccount.violations.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const occurredAt = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+    await program.methods
+      .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, evidenceByte)), "Outage", null, Buffer.from([]), occurredAt, occurredAt)
+      .accounts({
+        protocol: protocolPda,
+        provider: fresh.providerPda,
+        sla: freshSlaPda,
+        violation,
+        reporter: reporter.publicKey,
+        rentSponsor: null,
+        reporterAllowance: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([reporter])
+      .rpc();
+    return violation;
+  };
+
+  // Slash a fresh provider for one of its violations
+  const slashFor = (fresh: FreshProvider, freshSlaPda: PublicKey, violation: PublicKey) =>
+    program.methods
+      .slash()
+      .accounts({
+        protocol: protocolPda,
+        provider: fresh.providerPda,
+        sla: freshSlaPda,
+        violation,
+        stakeVault: fresh.vaultPda,
+        reporter: reporter.publicKey,
+        parentProvider: null,
+        attestation: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([reporter])
+      .rpc();
+
+  // Withdraw from a fresh provider's vault, signed by its authority
+  const withdrawFrom = (
+    fresh: FreshProvider,
+    amount: number,
+    closeOnZero = false
+  ) =>
+    program.methods
+      .withdrawStake(new anchor.BN(amount), closeOnZero, null)
+      .accounts({
+        protocol: protocolPda,
+        provider: fresh.providerPda,
+        stakeVault: fresh.vaultPda,
+        providerAuthority: fresh.authority.publicKey,
+        authority: fresh.authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([fresh.authority])
+      .rpc();
 
   before(async () => {
     // Airdrop SOL to test accounts
@@ -85,6 +201,93 @@ describe("Covenant Protocol", () => {
       expect(protocolAccount.totalStaked.toNumber()).to.equal(0);
       expect(protocolAccount.totalSlashed.toNumber()).to.equal(0);
     });
+
+    it("Sets the stake warning threshold", async () => {
+      await program.methods
+        .setStakeWarningThreshold(5000) // Warn within 50% above minimum
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.stakeWarningThresholdBps).to.equal(5000);
+    });
+
+    it("Estimates the full cost of onboarding", async () => {
+      const cost = await program.methods
+        .estimateOnboardingCost(new anchor.BN(STAKE_AMOUNT))
+        .accounts({ protocol: protocolPda })
+        .view();
+
+      const vaultRent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(cost.vaultRent.toNumber()).to.equal(vaultRent);
+      expect(cost.nameRecordRent.toNumber()).to.equal(0);
+      expect(cost.total.toNumber()).to.equal(
+        STAKE_AMOUNT + cost.providerRent.toNumber() + vaultRent + cost.slaRent.toNumber()
+      );
+    });
+
+    it("Reports protocol stats", async () => {
+      const stats = await program.methods
+        .getProtocolStats()
+        .accounts({ protocol: protocolPda })
+        .view();
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(stats.totalProviders.toNumber()).to.equal(protocolAccount.totalProviders.toNumber());
+      expect(stats.slashRateBps.toNumber()).to.equal(0);
+      expect(stats.slashedRolling.toNumber()).to.equal(0);
+    });
+
+    it("Reports a provider's health", async () => {
+      const fresh = await registerFreshProvider("HealthAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10, 4_000_000_000);
+      await program.methods
+        .setCapabilities([{ key: 1, value: new anchor.BN(128_000) }])
+        .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+      await reportAgainst(fresh, freshSlaPda, 1);
+      const health = (sla: PublicKey | null) =>
+        program.methods.providerHealth().accounts({ protocol: protocolPda, provider: fresh.providerPda, sla }).view();
+
+      const report = await health(freshSlaPda);
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(report.status).to.deep.equal({ active: {} });
+      expect(Buffer.from(report.region).toString()).to.equal("US");
+      expect(report.endpointVersion).to.equal(1);
+      expect(report.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+      expect(report.pendingViolations.toNumber()).to.equal(1);
+      expect(report.confirmedViolations.toNumber()).to.equal(0);
+      expect(report.dismissedViolations.toNumber()).to.equal(0);
+      expect(report.capabilities.map((capability) => capability.value.toNumber())).to.deep.equal([128_000]);
+      expect(report.inMaintenance).to.equal(false);
+      expect(report.season).to.equal(protocolAccount.season);
+      expect(report.seasonViolationCounts.reduce((sum, count) => sum + count.toNumber(), 0)).to.equal(1);
+      expect(report.violationCounts.reduce((sum, count) => sum + count.toNumber(), 0)).to.equal(1);
+      expect(report.slaLockedUntil.toNumber()).to.equal(4_000_000_000);
+
+      expect((await health(null)).slaLockedUntil).to.equal(null);
+    });
+
+    it("Fails to set the stake warning threshold without authority", async () => {
+      try {
+        await program.methods
+          .setStakeWarningThreshold(1000)
+          .accounts({
+            protocol: protocolPda,
+            authority: reporter.publicKey,
+          })
+          .signers([reporter])
+          .rpc();
+
+        expect.fail("Should have thrown Unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
   });
 
   describe("Provider Registration", () => {
@@ -93,12 +296,13 @@ describe("Covenant Protocol", () => {
       const serviceEndpoint = "https://api.testagent.ai/v1";
 
       const tx = await program.methods
-        .registerProvider(name, serviceEndpoint, new anchor.BN(STAKE_AMOUNT))
+        .registerProvider(name, serviceEndpoint, 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
         .accounts({
           protocol: protocolPda,
           provider: providerPda,
           stakeVault: vaultPda,
           providerAuthority: serviceProvider.publicKey,
+          nameRecord: null,
           systemProgram: SystemProgram.programId,
         })
         .signers([serviceProvider])
@@ -113,7 +317,9 @@ describe("Covenant Protocol", () => {
       expect(providerAccount.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
       expect(providerAccount.violations.toNumber()).to.equal(0);
       expect(providerAccount.successfulRequests.toNumber()).to.equal(0);
-      expect(providerAccount.isActive).to.equal(true);
+      expect(providerAccount.status).to.deep.equal({ active: {} });
+      expect(Buffer.from(providerAccount.region).toString()).to.equal("US");
+      expect(providerAccount.overcollateralizationBonusBps).to.equal(2000); // 4x above minimum, capped at 20%
 
       // Verify protocol stats updated
       const protocolAccount = await program.account.protocol.fetch(protocolPda);
@@ -121,6 +327,18 @@ describe("Covenant Protocol", () => {
       expect(protocolAccount.totalStaked.toNumber()).to.equal(STAKE_AMOUNT);
     });
 
+    it("Derives a provider's program addresses", async () => {
+      const addresses = await program.methods
+        .deriveAddresses(serviceProvider.publicKey)
+        .accounts({})
+        .view();
+
+      expect(addresses.protocol.toString()).to.equal(protocolPda.toString());
+      expect(addresses.provider.toString()).to.equal(providerPda.toString());
+      expect(addresses.stakeVault.toString()).to.equal(vaultPda.toString());
+      expect(addresses.sla.toString()).to.equal(slaPda.toString());
+    });
+
     it("Fails to register with insufficient stake", async () => {
       const insufficientProvider = Keypair.generate();
 
@@ -143,12 +361,13 @@ describe("Covenant Protocol", () => {
 
       try {
         await program.methods
-          .registerProvider("LowStake", "https://lowstake.ai", new anchor.BN(MIN_STAKE / 2))
+          .registerProvider("LowStake", "https://lowstake.ai", 1, new anchor.BN(MIN_STAKE / 2), Array.from(Buffer.from("US")))
           .accounts({
             protocol: protocolPda,
             provider: insufficientProviderPda,
             stakeVault: insufficientVaultPda,
             providerAuthority: insufficientProvider.publicKey,
+            nameRecord: null,
             systemProgram: SystemProgram.programId,
           })
           .signers([insufficientProvider])
@@ -159,229 +378,3672 @@ describe("Covenant Protocol", () => {
         expect(error.message).to.include("InsufficientStake");
       }
     });
-  });
 
-  describe("SLA Definition", () => {
-    it("Defines SLA terms for a provider", async () => {
-      const uptimeGuarantee = 95;      // 95%
-      const maxResponseTimeMs = 2000;   // 2 seconds
-      const accuracyGuarantee = 99;     // 99%
-      const penaltyPercentage = 10;     // 10% slash per violation
+    it("Rejects a taken name while unique names are enforced, until it is released", async () => {
+      const nameRecordPda = (name: string) => {
+        const key = Buffer.alloc(64);
+        Buffer.from(name.trim().toLowerCase()).copy(key);
+        return PublicKey.findProgramAddressSync(
+          [Buffer.from("name"), key.subarray(0, 32), key.subarray(32)],
+          program.programId
+        )[0];
+      };
+      const registerNamed = async (name: string, agent = Keypair.generate()) => {
+        const airdrop = await provider.connection.requestAirdrop(agent.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(airdrop);
+        await program.methods
+          .registerProvider(name, "https://named.ai", 1, new anchor.BN(MIN_STAKE), Array.from(Buffer.from("US")))
+          .accounts({
+            protocol: protocolPda,
+            provider: PublicKey.findProgramAddressSync(
+              [Buffer.from("provider"), agent.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            stakeVault: PublicKey.findProgramAddressSync(
+              [Buffer.from("vault"), agent.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            providerAuthority: agent.publicKey,
+            nameRecord: nameRecordPda(name),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([agent])
+          .rpc();
+      };
 
-      const tx = await program.methods
-        .defineSla(uptimeGuarantee, maxResponseTimeMs, accuracyGuarantee, penaltyPercentage)
+      await program.methods
+        .setUniqueNames(true)
         .accounts({
-          provider: providerPda,
-          sla: slaPda,
-          authority: serviceProvider.publicKey,
-          systemProgram: SystemProgram.programId,
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
         })
-        .signers([serviceProvider])
         .rpc();
 
-      console.log("SLA defined:", tx);
-
-      // Verify SLA state
-      const slaAccount = await program.account.sla.fetch(slaPda);
-      expect(slaAccount.provider.toString()).to.equal(providerPda.toString());
-      expect(slaAccount.uptimeGuarantee).to.equal(uptimeGuarantee);
-      expect(slaAccount.maxResponseTimeMs).to.equal(maxResponseTimeMs);
-      expect(slaAccount.accuracyGuarantee).to.equal(accuracyGuarantee);
-      expect(slaAccount.penaltyPercentage).to.equal(penaltyPercentage);
-      expect(slaAccount.isActive).to.equal(true);
-    });
-
-    it("Fails to define SLA with invalid percentage", async () => {
-      // Create a new provider for this test
-      const newProvider = Keypair.generate();
-      const airdrop = await provider.connection.requestAirdrop(
-        newProvider.publicKey,
-        2 * LAMPORTS_PER_SOL
-      );
-      await provider.connection.confirmTransaction(airdrop);
-
-      const [newProviderPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("provider"), newProvider.publicKey.toBuffer()],
-        program.programId
-      );
+      await registerNamed("NamedAgent");
+      try {
+        await registerNamed(" namedagent");
+        expect.fail("Should have thrown NameTaken error");
+      } catch (error) {
+        expect(error.message).to.include("NameTaken");
+      }
 
-      const [newVaultPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("vault"), newProvider.publicKey.toBuffer()],
-        program.programId
-      );
+      // A name can only be released once its provider has been closed
+      const holder = Keypair.generate();
+      await registerNamed("ReleasedAgent", holder);
+      const holderProvider = {
+        authority: holder,
+        providerPda: PublicKey.findProgramAddressSync(
+          [Buffer.from("provider"), holder.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        vaultPda: PublicKey.findProgramAddressSync(
+          [Buffer.from("vault"), holder.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      };
+      const releaseName = () =>
+        program.methods
+          .releaseName("ReleasedAgent")
+          .accounts({
+            nameRecord: nameRecordPda("ReleasedAgent"),
+            provider: holderProvider.providerPda,
+            authority: holder.publicKey,
+          })
+          .signers([holder])
+          .rpc();
+      try {
+        await releaseName();
+        expect.fail("Should have thrown ProviderStillRegistered error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderStillRegistered");
+      }
 
-      const [newSlaPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("sla"), newProviderPda.toBuffer()],
-        program.programId
-      );
+      await withdrawFrom(holderProvider, MIN_STAKE, true);
+      await releaseName();
+      expect(await provider.connection.getAccountInfo(nameRecordPda("ReleasedAgent"))).to.equal(null);
+      await registerNamed("ReleasedAgent");
 
-      // First register the provider
       await program.methods
-        .registerProvider("InvalidSLATest", "https://test.ai", new anchor.BN(STAKE_AMOUNT))
+        .setUniqueNames(false)
         .accounts({
           protocol: protocolPda,
-          provider: newProviderPda,
-          stakeVault: newVaultPda,
-          providerAuthority: newProvider.publicKey,
-          systemProgram: SystemProgram.programId,
+          authority: provider.wallet.publicKey,
         })
-        .signers([newProvider])
+        .rpc();
+    });
+
+    it("Updates the provider region", async () => {
+      await program.methods
+        .setRegion(Array.from(Buffer.from("EU")))
+        .accounts({
+          provider: providerPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
         .rpc();
 
-      // Try to define SLA with >100% uptime
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      expect(Buffer.from(providerAccount.region).toString()).to.equal("EU");
+
       try {
         await program.methods
-          .defineSla(101, 2000, 99, 10) // 101% uptime is invalid
+          .setRegion([0, 0])
           .accounts({
-            provider: newProviderPda,
-            sla: newSlaPda,
-            authority: newProvider.publicKey,
-            systemProgram: SystemProgram.programId,
+            provider: providerPda,
+            authority: serviceProvider.publicKey,
           })
-          .signers([newProvider])
+          .signers([serviceProvider])
           .rpc();
 
-        expect.fail("Should have thrown InvalidPercentage error");
+        expect.fail("Should have thrown InvalidRegion error");
       } catch (error) {
-        expect(error.message).to.include("InvalidPercentage");
+        expect(error.message).to.include("InvalidRegion");
       }
     });
-  });
-
-  describe("Recording Success", () => {
-    it("Records a successful service request", async () => {
-      const beforeProvider = await program.account.provider.fetch(providerPda);
-      const beforeCount = beforeProvider.successfulRequests.toNumber();
 
-      const tx = await program.methods
-        .recordSuccess()
+    it("Declares provider capabilities", async () => {
+      await program.methods
+        .setCapabilities([
+          { key: 1, value: new anchor.BN(128_000) },
+          { key: 3, value: new anchor.BN(1) },
+        ])
         .accounts({
           provider: providerPda,
-          caller: provider.wallet.publicKey,
+          authority: serviceProvider.publicKey,
         })
+        .signers([serviceProvider])
         .rpc();
 
-      console.log("Success recorded:", tx);
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.capabilities.length).to.equal(2);
+      expect(providerAccount.capabilities[0].value.toNumber()).to.equal(128_000);
 
-      const afterProvider = await program.account.provider.fetch(providerPda);
-      expect(afterProvider.successfulRequests.toNumber()).to.equal(beforeCount + 1);
+      try {
+        await program.methods
+          .setCapabilities([
+            { key: 1, value: new anchor.BN(1) },
+            { key: 1, value: new anchor.BN(2) },
+          ])
+          .accounts({
+            provider: providerPda,
+            authority: serviceProvider.publicKey,
+          })
+          .signers([serviceProvider])
+          .rpc();
+
+        expect.fail("Should have thrown DuplicateCapability error");
+      } catch (error) {
+        expect(error.message).to.include("DuplicateCapability");
+      }
     });
-  });
 
-  describe("Violation Reporting & Slashing", () => {
-    let violationPda: PublicKey;
+    it("Updates the service endpoint and its API version", async () => {
+      await program.methods
+        .updateEndpoint("https://v2.testagent.ai", 2)
+        .accounts({
+          provider: providerPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
 
-    it("Reports an SLA violation", async () => {
       const providerAccount = await program.account.provider.fetch(providerPda);
-      const violationIndex = providerAccount.violations.toNumber();
+      expect(providerAccount.serviceEndpoint).to.equal("https://v2.testagent.ai");
+      expect(providerAccount.endpointVersion).to.equal(2);
+    });
 
-      [violationPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("violation"),
-          providerPda.toBuffer(),
-          new anchor.BN(violationIndex).toArrayLike(Buffer, "le", 8),
-        ],
-        program.programId
+    it("Registers a provider and its SLA atomically", async () => {
+      const onboardingAgent = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        onboardingAgent.publicKey,
+        2 * LAMPORTS_PER_SOL
       );
+      await provider.connection.confirmTransaction(airdrop);
 
-      const evidenceHash = Buffer.alloc(32);
-      evidenceHash.fill(1); // Mock evidence hash
+      const [onboardingProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), onboardingAgent.publicKey.toBuffer()],
+        program.programId
+      );
+      const [onboardingVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), onboardingAgent.publicKey.toBuffer()],
+        program.programId
+      );
+      const [onboardingSlaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sla"), onboardingProviderPda.toBuffer()],
+        program.programId
+      );
 
-      const tx = await program.methods
-        .reportViolation(
-          { uptimeViolation: {} },
-          Array.from(evidenceHash),
-          "Service was down for 30 minutes on 2024-02-04"
+      await program.methods
+        .registerWithSla(
+          "OnboardingAgent",
+          "https://onboarding.ai",
+          1,
+          new anchor.BN(STAKE_AMOUNT),
+          Array.from(Buffer.from("US")),
+          99,
+          1000,
+          95,
+          10,
+          new anchor.BN(0)
         )
         .accounts({
-          provider: providerPda,
-          violation: violationPda,
-          reporter: reporter.publicKey,
+          register: {
+            protocol: protocolPda,
+            provider: onboardingProviderPda,
+            stakeVault: onboardingVaultPda,
+            providerAuthority: onboardingAgent.publicKey,
+            nameRecord: null,
+            systemProgram: SystemProgram.programId,
+          },
+          sla: onboardingSlaPda,
           systemProgram: SystemProgram.programId,
         })
-        .signers([reporter])
+        .signers([onboardingAgent])
         .rpc();
 
-      console.log("Violation reported:", tx);
-
-      // Verify violation state
-      const violationAccount = await program.account.violation.fetch(violationPda);
-      expect(violationAccount.provider.toString()).to.equal(providerPda.toString());
-      expect(violationAccount.reporter.toString()).to.equal(reporter.publicKey.toString());
-      expect(violationAccount.isResolved).to.equal(false);
-
-      // Verify provider violations incremented
-      const updatedProvider = await program.account.provider.fetch(providerPda);
-      expect(updatedProvider.violations.toNumber()).to.equal(violationIndex + 1);
+      const slaAccount = await program.account.sla.fetch(onboardingSlaPda);
+      expect(slaAccount.provider.toString()).to.equal(onboardingProviderPda.toString());
+      expect(slaAccount.isActive).to.equal(true);
     });
 
-    it("Slashes provider stake for violation", async () => {
-      const beforeProvider = await program.account.provider.fetch(providerPda);
-      const beforeStake = beforeProvider.stakeAmount.toNumber();
-      const beforeReporterBalance = await provider.connection.getBalance(reporter.publicKey);
-
-      const slaAccount = await program.account.sla.fetch(slaPda);
-      const expectedSlash = Math.floor((beforeStake * slaAccount.penaltyPercentage) / 100);
+    it("Joins an organization and aggregates its stake", async () => {
+      const [organizationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("organization"), provider.wallet.publicKey.toBuffer()],
+        program.programId
+      );
 
-      const tx = await program.methods
-        .slash()
+      await program.methods
+        .createOrganization("Test Fleet")
         .accounts({
-          protocol: protocolPda,
-          provider: providerPda,
-          sla: slaPda,
-          violation: violationPda,
-          stakeVault: vaultPda,
-          reporter: reporter.publicKey,
+          organization: organizationPda,
+          authority: provider.wallet.publicKey,
           systemProgram: SystemProgram.programId,
         })
-        .signers([reporter])
         .rpc();
 
-      console.log("Provider slashed:", tx);
+      await program.methods
+        .joinOrganization()
+        .accounts({
+          provider: providerPda,
+          organization: organizationPda,
+          authority: serviceProvider.publicKey,
+          organizationAuthority: provider.wallet.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
 
-      // Verify provider stake reduced
-      const afterProvider = await program.account.provider.fetch(providerPda);
-      expect(afterProvider.stakeAmount.toNumber()).to.equal(beforeStake - expectedSlash);
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const organization = await program.account.organization.fetch(organizationPda);
+      expect(providerAccount.org.toString()).to.equal(organizationPda.toString());
+      expect(organization.memberCount).to.equal(1);
+      expect(organization.totalStake.toNumber()).to.equal(providerAccount.stakeAmount.toNumber());
+    });
 
-      // Verify violation marked as resolved
-      const violationAccount = await program.account.violation.fetch(violationPda);
-      expect(violationAccount.isResolved).to.equal(true);
+    it("Rejects a retroactive maintenance window", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      try {
+        await program.methods
+          .declareMaintenance(new anchor.BN(now - 3600), new anchor.BN(now + 3600))
+          .accounts({
+            provider: providerPda,
+            authority: serviceProvider.publicKey,
+          })
+          .signers([serviceProvider])
+          .rpc();
 
-      // Verify protocol stats updated
-      const protocolAccount = await program.account.protocol.fetch(protocolPda);
-      expect(protocolAccount.totalSlashed.toNumber()).to.be.greaterThan(0);
+        expect.fail("Should have thrown InvalidMaintenanceWindow error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidMaintenanceWindow");
+      }
+    });
 
-      console.log(`Slashed ${expectedSlash / LAMPORTS_PER_SOL} SOL from provider`);
+    it("Keeps ended maintenance windows until reports about them expire", async () => {
+      const fresh = await registerFreshProvider("MaintainedAgent");
+      const declare = (start: number, end: number) =>
+        program.methods
+          .declareMaintenance(new anchor.BN(start), new anchor.BN(end))
+          .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+          .signers([fresh.authority])
+          .rpc();
+      const setEvidenceTtl = (ttlSeconds: number) =>
+        program.methods
+          .setEvidenceTtl(new anchor.BN(ttlSeconds))
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .rpc();
+
+      const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+      await declare(now + 1, now + 2);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      for (let i = 1; i <= 3; i++) {
+        await declare(now + i * 3600, now + i * 3600 + 60);
+      }
+
+      // The ended window still holds its slot while a report could fall inside it
+      try {
+        await declare(now + 5 * 3600, now + 5 * 3600 + 60);
+        expect.fail("Should have thrown TooManyMaintenanceWindows error");
+      } catch (error) {
+        expect(error.message).to.include("TooManyMaintenanceWindows");
+      }
+
+      await setEvidenceTtl(1);
+      await declare(now + 5 * 3600, now + 5 * 3600 + 60);
+      await setEvidenceTtl(30 * 24 * 60 * 60);
+
+      const [maintenancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("maintenance"), fresh.providerPda.toBuffer()],
+        program.programId
+      );
+      const schedule = await program.account.maintenanceWindow.fetch(maintenancePda);
+      expect(schedule.windows.map((window) => window.start.toNumber())).to.not.include(now + 1);
+      expect(schedule.windows.length).to.equal(4);
+    });
+
+    it("Attaches a sub-provider to a parent", async () => {
+      const subAgent = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        subAgent.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [subProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), subAgent.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [subVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), subAgent.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerProvider("SubAgent", "https://sub.testagent.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: subProviderPda,
+          stakeVault: subVaultPda,
+          providerAuthority: subAgent.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([subAgent])
+        .rpc();
+
+      await program.methods
+        .setParentProvider()
+        .accounts({
+          provider: subProviderPda,
+          parentProvider: providerPda,
+          authority: subAgent.publicKey,
+          parentAuthority: serviceProvider.publicKey,
+        })
+        .signers([subAgent, serviceProvider])
+        .rpc();
+
+      const subProviderAccount = await program.account.provider.fetch(subProviderPda);
+      expect(subProviderAccount.parent.toString()).to.equal(providerPda.toString());
+
+      const parentAccount = await program.account.provider.fetch(providerPda);
+      expect(parentAccount.subProviderCount).to.equal(1);
     });
   });
 
-  describe("Stake Withdrawal", () => {
-    it("Allows provider to withdraw partial stake", async () => {
+  describe("SLA Definition", () => {
+    it("Defines SLA terms for a provider", async () => {
+      const uptimeGuarantee = 95;      // 95%
+      const maxResponseTimeMs = 2000;   // 2 seconds
+      const accuracyGuarantee = 99;     // 99%
+      const penaltyPercentage = 10;     // 10% slash per violation
+
+      const tx = await program.methods
+        .defineSla(uptimeGuarantee, maxResponseTimeMs, accuracyGuarantee, penaltyPercentage, new anchor.BN(0))
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          authority: serviceProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      console.log("SLA defined:", tx);
+
+      // Verify SLA state
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      expect(slaAccount.provider.toString()).to.equal(providerPda.toString());
+      expect(slaAccount.uptimeGuarantee).to.equal(uptimeGuarantee);
+      expect(slaAccount.maxResponseTimeMs).to.equal(maxResponseTimeMs);
+      expect(slaAccount.accuracyGuarantee).to.equal(accuracyGuarantee);
+      expect(slaAccount.penaltyPercentage).to.equal(penaltyPercentage);
+      expect(slaAccount.isActive).to.equal(true);
+    });
+
+    it("Raises the penalty immediately but only schedules a decrease", async () => {
+      await program.methods
+        .increasePenalty(15)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      await program.methods
+        .decreasePenalty(10)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      // The decrease waits out its notice period; the raised penalty stays in force
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      expect(slaAccount.penaltyPercentage).to.equal(15);
+      expect(slaAccount.pendingPenaltyPercentage).to.equal(10);
+    });
+
+    it("Fails to define SLA with invalid percentage", async () => {
+      // Create a new provider for this test
+      const newProvider = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        newProvider.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [newProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), newProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [newVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), newProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [newSlaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sla"), newProviderPda.toBuffer()],
+        program.programId
+      );
+
+      // First register the provider
+      await program.methods
+        .registerProvider("InvalidSLATest", "https://test.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: newProviderPda,
+          stakeVault: newVaultPda,
+          providerAuthority: newProvider.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([newProvider])
+        .rpc();
+
+      // Try to define SLA with >100% uptime
+      try {
+        await program.methods
+          .defineSla(101, 2000, 99, 10, new anchor.BN(0)) // 101% uptime is invalid
+          .accounts({
+            protocol: protocolPda,
+            provider: newProviderPda,
+            sla: newSlaPda,
+            authority: newProvider.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([newProvider])
+          .rpc();
+
+        expect.fail("Should have thrown InvalidPercentage error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidPercentage");
+      }
+    });
+  });
+
+  describe("Recording Success", () => {
+    it("Records a successful service request", async () => {
       const beforeProvider = await program.account.provider.fetch(providerPda);
-      const currentStake = beforeProvider.stakeAmount.toNumber();
-      const withdrawAmount = currentStake - MIN_STAKE; // Withdraw down to minimum
+      const beforeCount = beforeProvider.successfulRequests.toNumber();
 
-      if (withdrawAmount > 0) {
-        const tx = await program.methods
-          .withdrawStake(new anchor.BN(withdrawAmount))
+      const tx = await program.methods
+        .recordSuccess(null)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          caller: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      console.log("Success recorded:", tx);
+
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.successfulRequests.toNumber()).to.equal(beforeCount + 1);
+      // No violations yet: the full score, plus the overcollateralization bonus
+      expect(afterProvider.reputationScore.toNumber()).to.equal(
+        Math.floor(((beforeCount + 1) * REPUTATION_SCALE * (10000 + afterProvider.overcollateralizationBonusBps)) / 10000)
+      );
+    });
+
+    it("Ignores a retried request id", async () => {
+      const requestId = Array.from(Keypair.generate().publicKey.toBuffer().subarray(0, 16));
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const beforeCount = beforeProvider.successfulRequests.toNumber();
+
+      for (let attempt = 0; attempt < 2; attempt++) {
+        await program.methods
+          .recordSuccess(requestId)
           .accounts({
             protocol: protocolPda,
             provider: providerPda,
-            stakeVault: vaultPda,
-            providerAuthority: serviceProvider.publicKey,
-            authority: serviceProvider.publicKey,
-            systemProgram: SystemProgram.programId,
+            caller: provider.wallet.publicKey,
           })
-          .signers([serviceProvider])
           .rpc();
+      }
 
-        console.log("Stake withdrawn:", tx);
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.successfulRequests.toNumber()).to.equal(beforeCount + 1);
+    });
 
-        // Verify provider stake reduced
-        const afterProvider = await program.account.provider.fetch(providerPda);
-        expect(afterProvider.stakeAmount.toNumber()).to.equal(MIN_STAKE);
-        expect(afterProvider.isActive).to.equal(true); // Still active with minimum stake
+    it("Refreshes cached reputation after a weight change", async () => {
+      const weights = [4, 2, 3, 4, 1];
+      await program.methods
+        .setViolationWeights(weights)
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      await program.methods
+        .refreshProviderMetrics()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+        })
+        .rpc();
+
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const penalty = providerAccount.seasonViolationCounts.reduce(
+        (sum, count, i) => sum + count.toNumber() * weights[i],
+        0
+      );
+      const base = Math.floor((providerAccount.seasonSuccesses.toNumber() * REPUTATION_SCALE) / (1 + penalty));
+      expect(providerAccount.reputationScore.toNumber()).to.equal(
+        Math.floor((base * (10000 + providerAccount.overcollateralizationBonusBps)) / 10000)
+      );
+    });
+  });
+
+  describe("Blacklisting", () => {
+    it("Blocks a blacklisted provider from recording successes", async () => {
+      await program.methods
+        .blacklistProvider()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      let providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.status).to.deep.equal({ banned: {} });
+
+      try {
+        await program.methods
+          .recordSuccess(null)
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            caller: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown ProviderBlacklisted error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderBlacklisted");
       }
+
+      await program.methods
+        .unblacklistProvider()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.status).to.deep.equal({ active: {} });
+
+      // Only a banned provider can be unbanned
+      try {
+        await program.methods
+          .unblacklistProvider()
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown ProviderNotBanned error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderNotBanned");
+      }
+    });
+
+    it("Still takes reports against a banned provider, holding its stake until they're resolved", async () => {
+      const fresh = await registerFreshProvider("BannedReportAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      await program.methods
+        .blacklistProvider()
+        .accounts({ protocol: protocolPda, provider: fresh.providerPda, authority: provider.wallet.publicKey })
+        .rpc();
+
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+      expect((await program.account.provider.fetch(fresh.providerPda)).pendingViolations.toNumber()).to.equal(1);
+      try {
+        await withdrawFrom(fresh, STAKE_AMOUNT, true);
+        expect.fail("Should have thrown PendingViolations error");
+      } catch (error) {
+        expect(error.message).to.include("PendingViolations");
+      }
+
+      await slashFor(fresh, freshSlaPda, violation);
+      const banned = await program.account.provider.fetch(fresh.providerPda);
+      expect(banned.status).to.deep.equal({ banned: {} });
+      await withdrawFrom(fresh, banned.stakeAmount.toNumber());
+    });
+  });
+
+  describe("Reputation Floor", () => {
+    it("Blocks low-reputation providers until probation lets them rebuild", async () => {
+      const fresh = await registerFreshProvider("FloorAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      // A report alone leaves the provider active, with a violation on record
+      await reportAgainst(fresh, freshSlaPda, 1);
+
+      const setFloor = (floor: number) =>
+        program.methods
+          .setMinReputationToServe(new anchor.BN(floor))
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .rpc();
+      const recordSuccess = () =>
+        program.methods
+          .recordSuccess(null)
+          .accounts({ protocol: protocolPda, provider: fresh.providerPda, caller: provider.wallet.publicKey })
+          .rpc();
+      const grantProbation = () =>
+        program.methods
+          .grantProbation()
+          .accounts({ protocol: protocolPda, provider: fresh.providerPda, authority: provider.wallet.publicKey })
+          .rpc();
+
+      await setFloor(1);
+      try {
+        await recordSuccess();
+        expect.fail("Should have thrown ReputationTooLow error");
+      } catch (error) {
+        expect(error.message).to.include("ReputationTooLow");
+      }
+
+      await grantProbation();
+      expect((await program.account.provider.fetch(fresh.providerPda)).status).to.deep.equal({ probation: {} });
+
+      // One success clears the floor and ends probation
+      await recordSuccess();
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.status).to.deep.equal({ active: {} });
+      expect(providerAccount.reputationScore.toNumber()).to.be.at.least(1);
+
+      try {
+        await grantProbation();
+        expect.fail("Should have thrown ReputationAboveFloor error");
+      } catch (error) {
+        expect(error.message).to.include("ReputationAboveFloor");
+      }
+
+      await setFloor(0);
+    });
+
+    it("Applies the floor only once enough distinct reporters have filed", async () => {
+      const fresh = await registerFreshProvider("QuorumAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const configure = (method: any) =>
+        method.accounts({ protocol: protocolPda, authority: provider.wallet.publicKey }).rpc();
+      const recordSuccess = () =>
+        program.methods
+          .recordSuccess(null)
+          .accounts({ protocol: protocolPda, provider: fresh.providerPda, caller: provider.wallet.publicKey })
+          .rpc();
+
+      try {
+        await configure(program.methods.setMinDistinctReporters(9));
+        expect.fail("Should have thrown TooManyDistinctReporters error");
+      } catch (error) {
+        expect(error.message).to.include("TooManyDistinctReporters");
+      }
+
+      await configure(program.methods.setMinDistinctReporters(2));
+      await configure(program.methods.setMinReputationToServe(new anchor.BN(1_000_000_000)));
+
+      // Repeated reports from a lone reporter don't shut the provider out
+      await reportAgainst(fresh, freshSlaPda, 1);
+      await reportAgainst(fresh, freshSlaPda, 2);
+      await recordSuccess();
+      expect((await program.account.provider.fetch(fresh.providerPda)).distinctReporterCount).to.equal(1);
+
+      const secondReporter = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(secondReporter.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      const [violation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), fresh.providerPda.toBuffer(), providerAccount.violations.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const occurredAt = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+      await program.methods
+        .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, 3)), "Outage", null, Buffer.from([]), occurredAt, occurredAt)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          sla: freshSlaPda,
+          violation,
+          reporter: secondReporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([secondReporter])
+        .rpc();
+
+      try {
+        await recordSuccess();
+        expect.fail("Should have thrown ReputationTooLow error");
+      } catch (error) {
+        expect(error.message).to.include("ReputationTooLow");
+      }
+
+      await configure(program.methods.setMinReputationToServe(new anchor.BN(0)));
+      await configure(program.methods.setMinDistinctReporters(0));
+    });
+  });
+
+  describe("Complaints", () => {
+    it("Files and resolves a consumer complaint", async () => {
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const [complaintPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("complaint"),
+          providerPda.toBuffer(),
+          beforeProvider.complaints.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .fileComplaint({ latency: {} }, Array(32).fill(7))
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          complaint: complaintPda,
+          consumer: reporter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      let providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.openComplaints.toNumber()).to.equal(1);
+      expect(providerAccount.reputationScore.toNumber()).to.be.lessThan(
+        beforeProvider.reputationScore.toNumber()
+      );
+
+      await program.methods
+        .resolveComplaint()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          complaint: complaintPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.openComplaints.toNumber()).to.equal(0);
+      expect(providerAccount.reputationScore.toNumber()).to.equal(
+        beforeProvider.reputationScore.toNumber()
+      );
+
+      const complaintAccount = await program.account.complaint.fetch(complaintPda);
+      expect(complaintAccount.isResolved).to.equal(true);
+    });
+  });
+
+  describe("Violation Reporting & Slashing", () => {
+    let violationPda: PublicKey;
+    // A breach time inside the SLA's active period
+    const slaStart = async () => (await program.account.sla.fetch(slaPda)).createdAt;
+
+    it("Rejects a report without evidence", async () => {
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const [emptyEvidencePda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("violation"),
+          providerPda.toBuffer(),
+          providerAccount.violations.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .reportViolation({ uptimeViolation: {} }, Array(32).fill(0), "No evidence attached", null, Buffer.from([]), await slaStart(), await slaStart())
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            sla: slaPda,
+            violation: emptyEvidencePda,
+            reporter: reporter.publicKey,
+            rentSponsor: null,
+            reporterAllowance: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([reporter])
+          .rpc();
+
+        expect.fail("Should have thrown MissingEvidence error");
+      } catch (error) {
+        expect(error.message).to.include("MissingEvidence");
+      }
+    });
+
+    it("Reports an SLA violation", async () => {
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const violationIndex = providerAccount.violations.toNumber();
+
+      [violationPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("violation"),
+          providerPda.toBuffer(),
+          new anchor.BN(violationIndex).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      const evidenceHash = Buffer.alloc(32);
+      evidenceHash.fill(1); // Mock evidence hash
+
+      const beforeReporterBalance = await provider.connection.getBalance(reporter.publicKey);
+
+      const tx = await program.methods
+        .reportViolation(
+          { uptimeViolation: {} },
+          Array.from(evidenceHash),
+          "Service was down for 30 minutes on 2024-02-04",
+          "https://monitor.example/evidence/uptime-2024-02-04",
+          Buffer.from([]),
+          await slaStart(),
+          await slaStart()
+        )
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: violationPda,
+          reporter: reporter.publicKey,
+          rentSponsor: provider.wallet.publicKey, // Sponsor covers the violation account rent
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      console.log("Violation reported:", tx);
+
+      // Reporter paid neither rent nor fees
+      const afterReporterBalance = await provider.connection.getBalance(reporter.publicKey);
+      expect(afterReporterBalance).to.equal(beforeReporterBalance);
+
+      // Verify violation state
+      const violationAccount = await program.account.violation.fetch(violationPda);
+      expect(violationAccount.provider.toString()).to.equal(providerPda.toString());
+      expect(violationAccount.reporter.toString()).to.equal(reporter.publicKey.toString());
+      expect(violationAccount.isResolved).to.equal(false);
+
+      // Verify provider violations incremented and reputation discounted by the uptime weight
+      const updatedProvider = await program.account.provider.fetch(providerPda);
+      expect(updatedProvider.violations.toNumber()).to.equal(violationIndex + 1);
+      expect(updatedProvider.violationCounts[0].toNumber()).to.equal(1);
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      const base = Math.floor(
+        (updatedProvider.successfulRequests.toNumber() * REPUTATION_SCALE) / (1 + protocolAccount.violationWeights[0])
+      );
+      expect(updatedProvider.reputationScore.toNumber()).to.equal(
+        Math.floor((base * (10000 + updatedProvider.overcollateralizationBonusBps)) / 10000)
+      );
+    });
+
+    it("Reports the most a reporter could recover", async () => {
+      const recoverable = await program.methods
+        .maxRecoverable()
+        .accounts({ protocol: protocolPda, provider: providerPda, sla: slaPda, violation: null, attestation: null })
+        .view();
+
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      const penalty = Math.floor((providerAccount.stakeAmount.toNumber() * slaAccount.penaltyPercentage) / 100);
+      expect(recoverable.toNumber()).to.equal(
+        penalty + Math.floor((penalty * protocolAccount.firstReporterBonusBps) / 10000)
+      );
+    });
+
+    it("Slashes provider stake for violation", async () => {
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const beforeStake = beforeProvider.stakeAmount.toNumber();
+      const beforeReporterBalance = await provider.connection.getBalance(reporter.publicKey);
+
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      const expectedSlash = Math.floor((beforeStake * slaAccount.penaltyPercentage) / 100);
+
+      const tx = await program.methods
+        .slash()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: violationPda,
+          stakeVault: vaultPda,
+          reporter: reporter.publicKey,
+          parentProvider: null,
+          attestation: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      console.log("Provider slashed:", tx);
+
+      // Verify provider stake reduced
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.stakeAmount.toNumber()).to.equal(beforeStake - expectedSlash);
+      expect(afterProvider.status).to.deep.equal({ probation: {} });
+      expect(afterProvider.confirmedViolations.toNumber()).to.equal(
+        beforeProvider.confirmedViolations.toNumber() + 1
+      );
+
+      // Verify violation marked as resolved
+      const violationAccount = await program.account.violation.fetch(violationPda);
+      expect(violationAccount.isResolved).to.equal(true);
+
+      // Verify protocol stats updated
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.totalSlashed.toNumber()).to.be.greaterThan(0);
+
+      console.log(`Slashed ${expectedSlash / LAMPORTS_PER_SOL} SOL from provider`);
+    });
+
+    it("Tops up slashed stake from the funding source", async () => {
+      const [fundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), providerPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .configureAutoTopup(new anchor.BN(STAKE_AMOUNT))
+        .accounts({
+          provider: providerPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      // Pre-fund the funding PDA with more than the shortfall
+      const fundTx = new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: serviceProvider.publicKey,
+          toPubkey: fundingPda,
+          lamports: 0.2 * LAMPORTS_PER_SOL,
+        })
+      );
+      await provider.sendAndConfirm(fundTx, [serviceProvider]);
+
+      await program.methods
+        .triggerTopup()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          fundingSource: fundingPda,
+          stakeVault: vaultPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.fundingSource.toString()).to.equal(fundingPda.toString());
+      expect(providerAccount.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+    });
+
+    it("Settles an acknowledged violation at a reduced penalty", async () => {
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const beforeStake = beforeProvider.stakeAmount.toNumber();
+
+      const [settledViolationPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("violation"),
+          providerPda.toBuffer(),
+          beforeProvider.violations.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .reportViolation(
+          { responseTimeViolation: {} },
+          Array.from(Buffer.alloc(32, 2)),
+          "p99 latency exceeded 2s for an hour",
+          null,
+          Buffer.from([]),
+          await slaStart(),
+          await slaStart()
+        )
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: settledViolationPda,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      await program.methods
+        .acknowledgeViolation(Buffer.from([]))
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: settledViolationPda,
+          stakeVault: vaultPda,
+          reporter: reporter.publicKey,
+          parentProvider: null,
+          authority: serviceProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      const expectedSlash = Math.floor(
+        (beforeStake * slaAccount.penaltyPercentage * protocolAccount.settlementDiscountBps) / (100 * 10000)
+      );
+
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.stakeAmount.toNumber()).to.equal(beforeStake - expectedSlash);
+
+      const violationAccount = await program.account.violation.fetch(settledViolationPda);
+      expect(violationAccount.isResolved).to.equal(true);
+    });
+
+    it("Settles only the acknowledged claims of a bundled report", async () => {
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const beforeStake = beforeProvider.stakeAmount.toNumber();
+
+      const [bundledViolationPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("violation"),
+          providerPda.toBuffer(),
+          beforeProvider.violations.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .reportViolation(
+          { accuracyViolation: {} },
+          Array.from(Buffer.alloc(32, 3)),
+          "Wrong answers, stale data and truncated output",
+          null,
+          Buffer.from([1, 2, 3]),
+          await slaStart(),
+          await slaStart()
+        )
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: bundledViolationPda,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      await program.methods
+        .acknowledgeViolation(Buffer.from([2]))
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: bundledViolationPda,
+          stakeVault: vaultPda,
+          reporter: reporter.publicKey,
+          parentProvider: null,
+          authority: serviceProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      const slaAccount = await program.account.sla.fetch(slaPda);
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      const fullSettlement = Math.floor(
+        (beforeStake * slaAccount.penaltyPercentage * protocolAccount.settlementDiscountBps) / (100 * 10000)
+      );
+
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.stakeAmount.toNumber()).to.equal(beforeStake - Math.floor(fullSettlement / 3));
+
+      // The two disputed claims stay open
+      const violationAccount = await program.account.violation.fetch(bundledViolationPda);
+      expect(violationAccount.isResolved).to.equal(false);
+      expect(violationAccount.settledClaims).to.equal(0b010);
+    });
+
+    it("Resolves a cured violation without slashing", async () => {
+      const setCureWindow = (seconds: number) =>
+        program.methods
+          .setCureWindow(new anchor.BN(seconds))
+          .accounts({
+            protocol: protocolPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+
+      await setCureWindow(3600);
+
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const [curedViolationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), providerPda.toBuffer(), beforeProvider.violations.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      await program.methods
+        .reportViolation({ serviceUnavailable: {} }, Array.from(Buffer.alloc(32, 8)), "Brief outage", null, Buffer.from([]), await slaStart(), await slaStart())
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: curedViolationPda,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      try {
+        await program.methods
+          .slash()
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            sla: slaPda,
+            violation: curedViolationPda,
+            stakeVault: vaultPda,
+            reporter: reporter.publicKey,
+            parentProvider: null,
+            attestation: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([reporter])
+          .rpc();
+
+        expect.fail("Should have thrown CureWindowOpen error");
+      } catch (error) {
+        expect(error.message).to.include("CureWindowOpen");
+      }
+
+      await program.methods
+        .cureViolation(Array.from(Buffer.alloc(32, 9)))
+        .accounts({
+          provider: providerPda,
+          violation: curedViolationPda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      await program.methods
+        .reviewCure(true)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          violation: curedViolationPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const violationAccount = await program.account.violation.fetch(curedViolationPda);
+      expect(violationAccount.isCured).to.equal(true);
+      expect(violationAccount.isResolved).to.equal(true);
+
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      expect(afterProvider.stakeAmount.toNumber()).to.equal(beforeProvider.stakeAmount.toNumber());
+      expect(afterProvider.confirmedViolations.toNumber()).to.equal(beforeProvider.confirmedViolations.toNumber() + 1);
+
+      await setCureWindow(0);
+    });
+
+    it("Reimburses a sponsored reporter from the provider's allowance", async () => {
+      const [allowancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("allowance"), providerPda.toBuffer(), reporter.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .sponsorReporter(reporter.publicKey, new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          provider: providerPda,
+          allowance: allowancePda,
+          authority: serviceProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const [sponsoredViolationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), providerPda.toBuffer(), providerAccount.violations.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const beforeReporterBalance = await provider.connection.getBalance(reporter.publicKey);
+
+      await program.methods
+        .reportViolation({ responseTimeViolation: {} }, Array.from(Buffer.alloc(32, 10)), "Slow responses", null, Buffer.from([]), await slaStart(), await slaStart())
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: sponsoredViolationPda,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: allowancePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      expect(await provider.connection.getBalance(reporter.publicKey)).to.equal(beforeReporterBalance);
+      const allowance = await program.account.reporterAllowance.fetch(allowancePda);
+      expect(allowance.spent.toNumber()).to.be.greaterThan(0);
+
+      await program.methods
+        .revokeSponsorship()
+        .accounts({
+          provider: providerPda,
+          allowance: allowancePda,
+          authority: serviceProvider.publicKey,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      expect(await program.account.reporterAllowance.fetchNullable(allowancePda)).to.equal(null);
+    });
+
+    it("Records a provider self-attestation for a past period", async () => {
+      const periodEnd = await slaStart();
+      const periodStart = periodEnd.subn(3600);
+      const [attestationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("attestation"), providerPda.toBuffer(), periodStart.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      await program.methods
+        .selfAttest(periodStart, periodEnd, 100, 50, 100)
+        .accounts({
+          provider: providerPda,
+          attestation: attestationPda,
+          authority: serviceProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([serviceProvider])
+        .rpc();
+
+      const attestation = await program.account.selfAttestation.fetch(attestationPda);
+      expect(attestation.provider.toString()).to.equal(providerPda.toString());
+      expect(attestation.uptimePercentage).to.equal(100);
+      expect(attestation.periodEnd.toNumber()).to.equal(periodEnd.toNumber());
+    });
+
+    it("Never reuses a violation seed after the account is closed", async () => {
+      const violationPdaAt = (index: anchor.BN) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("violation"), providerPda.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        )[0];
+
+      const reportAndExpire = async (violation: PublicKey) => {
+        await program.methods
+          .reportViolation({ accuracyViolation: {} }, Array.from(Buffer.alloc(32, 4)), "Stale evidence", null, Buffer.from([]), await slaStart(), await slaStart())
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            sla: slaPda,
+            violation,
+            reporter: reporter.publicKey,
+            rentSponsor: null,
+            reporterAllowance: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([reporter])
+          .rpc();
+
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+
+        await program.methods
+          .expireViolation()
+          .accounts({
+            provider: providerPda,
+            violation,
+            rentPayer: reporter.publicKey,
+            cranker: provider.wallet.publicKey,
+          })
+          .rpc();
+      };
+
+      await program.methods
+        .setEvidenceTtl(new anchor.BN(1))
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const firstPda = violationPdaAt(beforeProvider.violations);
+      await reportAndExpire(firstPda);
+      expect(await provider.connection.getAccountInfo(firstPda)).to.equal(null);
+
+      const afterProvider = await program.account.provider.fetch(providerPda);
+      const secondPda = violationPdaAt(afterProvider.violations);
+      expect(secondPda.toString()).to.not.equal(firstPda.toString());
+      await reportAndExpire(secondPda);
+
+      const finalProvider = await program.account.provider.fetch(providerPda);
+      expect(finalProvider.violations.toNumber()).to.equal(beforeProvider.violations.toNumber() + 2);
+      expect(finalProvider.dismissedViolations.toNumber()).to.equal(
+        beforeProvider.dismissedViolations.toNumber() + 2
+      );
+
+      await program.methods
+        .setEvidenceTtl(new anchor.BN(30 * 24 * 60 * 60))
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+    });
+
+    it("Trips the circuit breaker on excess slash volume", async () => {
+      const setThreshold = (threshold: number) =>
+        program.methods
+          .setCircuitBreakerThreshold(new anchor.BN(threshold))
+          .accounts({
+            protocol: protocolPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+
+      const reportAccounts = async () => {
+        const providerAccount = await program.account.provider.fetch(providerPda);
+        const [violation] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("violation"),
+            providerPda.toBuffer(),
+            providerAccount.violations.toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId
+        );
+        return {
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: null,
+          systemProgram: SystemProgram.programId,
+        };
+      };
+
+      await setThreshold(1);
+
+      const accounts = await reportAccounts();
+      await program.methods
+        .reportViolation({ other: {} }, Array.from(Buffer.alloc(32, 5)), "Suspicious burst", null, Buffer.from([]), await slaStart(), await slaStart())
+        .accounts(accounts)
+        .signers([reporter])
+        .rpc();
+
+      await program.methods
+        .slash()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: accounts.violation,
+          stakeVault: vaultPda,
+          reporter: reporter.publicKey,
+          parentProvider: null,
+          attestation: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.paused).to.equal(true);
+
+      try {
+        await program.methods
+          .reportViolation({ other: {} }, Array.from(Buffer.alloc(32, 6)), "While paused", null, Buffer.from([]), await slaStart(), await slaStart())
+          .accounts(await reportAccounts())
+          .signers([reporter])
+          .rpc();
+
+        expect.fail("Should have thrown ProtocolPaused error");
+      } catch (error) {
+        expect(error.message).to.include("ProtocolPaused");
+      }
+
+      await program.methods
+        .unpauseProtocol()
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      await setThreshold(0);
+
+      const resumedProtocol = await program.account.protocol.fetch(protocolPda);
+      expect(resumedProtocol.paused).to.equal(false);
+    });
+  });
+
+  describe("Emergency Reporting", () => {
+    it("Lets the authority file an emergency violation", async () => {
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      const [emergencyViolationPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("violation"),
+          providerPda.toBuffer(),
+          providerAccount.violations.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .emergencyReport(
+          { serviceUnavailable: {} },
+          Array.from(Buffer.alloc(32, 3)),
+          "Endpoint is returning attacker-controlled responses"
+        )
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          sla: slaPda,
+          violation: emergencyViolationPda,
+          authority: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const violationAccount = await program.account.violation.fetch(emergencyViolationPda);
+      expect(violationAccount.isEmergency).to.equal(true);
+      expect(violationAccount.sla.toString()).to.equal(slaPda.toString());
+      expect(violationAccount.reporter.toString()).to.equal(provider.wallet.publicKey.toString());
+    });
+  });
+
+  describe("Stake Withdrawal", () => {
+    it("Rejects withdrawals while frozen", async () => {
+      await program.methods
+        .freezeWithdrawals()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      try {
+        await program.methods
+          .withdrawStake(new anchor.BN(1), false, null)
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            stakeVault: vaultPda,
+            providerAuthority: serviceProvider.publicKey,
+            authority: serviceProvider.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([serviceProvider])
+          .rpc();
+
+        expect.fail("Should have thrown WithdrawalsFrozen error");
+      } catch (error) {
+        expect(error.message).to.include("WithdrawalsFrozen");
+      }
+
+      await program.methods
+        .unfreezeWithdrawals()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const providerAccount = await program.account.provider.fetch(providerPda);
+      expect(providerAccount.withdrawalsFrozen).to.equal(false);
+    });
+
+    it("Allows provider to withdraw partial stake", async () => {
+      const beforeProvider = await program.account.provider.fetch(providerPda);
+      const currentStake = beforeProvider.stakeAmount.toNumber();
+      const withdrawAmount = currentStake - MIN_STAKE; // Withdraw down to minimum
+
+      if (withdrawAmount > 0) {
+        // A stale expectation of the stake is rejected
+        try {
+          await program.methods
+            .withdrawStake(new anchor.BN(withdrawAmount), false, new anchor.BN(currentStake + 1))
+            .accounts({
+              protocol: protocolPda,
+              provider: providerPda,
+              stakeVault: vaultPda,
+              providerAuthority: serviceProvider.publicKey,
+              authority: serviceProvider.publicKey,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([serviceProvider])
+            .rpc();
+
+          expect.fail("Should have thrown StakeChangedUnexpectedly error");
+        } catch (error) {
+          expect(error.message).to.include("StakeChangedUnexpectedly");
+        }
+
+        const tx = await program.methods
+          .withdrawStake(new anchor.BN(withdrawAmount), false, new anchor.BN(currentStake))
+          .accounts({
+            protocol: protocolPda,
+            provider: providerPda,
+            stakeVault: vaultPda,
+            providerAuthority: serviceProvider.publicKey,
+            authority: serviceProvider.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([serviceProvider])
+          .rpc();
+
+        console.log("Stake withdrawn:", tx);
+
+        // Verify provider stake reduced
+        const afterProvider = await program.account.provider.fetch(providerPda);
+        expect(afterProvider.stakeAmount.toNumber()).to.equal(MIN_STAKE);
+        expect(afterProvider.status).to.deep.equal({ probation: {} }); // Still serving with minimum stake
+      }
+    });
+
+    it("Closes the provider account on full withdrawal", async () => {
+      const exitingProvider = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        exitingProvider.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [exitingProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), exitingProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [exitingVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), exitingProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerProvider("ExitingAgent", "https://exiting.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: exitingProviderPda,
+          stakeVault: exitingVaultPda,
+          providerAuthority: exitingProvider.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([exitingProvider])
+        .rpc();
+
+      await program.methods
+        .withdrawStake(new anchor.BN(STAKE_AMOUNT), true, null)
+        .accounts({
+          protocol: protocolPda,
+          provider: exitingProviderPda,
+          stakeVault: exitingVaultPda,
+          providerAuthority: exitingProvider.publicKey,
+          authority: exitingProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([exitingProvider])
+        .rpc();
+
+      const closedProvider = await program.account.provider.fetchNullable(exitingProviderPda);
+      expect(closedProvider).to.equal(null);
+
+      // Closing also returns the vault's rent reserve
+      const vaultBalance = await provider.connection.getBalance(exitingVaultPda);
+      expect(vaultBalance).to.equal(0);
+    });
+
+    it("Caps withdrawals per period by reputation tier", async () => {
+      const limitedProvider = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(limitedProvider.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [limitedProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), limitedProvider.publicKey.toBuffer()],
+        program.programId
+      );
+      const [limitedVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), limitedProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerProvider("LimitedAgent", "https://limited.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: limitedProviderPda,
+          stakeVault: limitedVaultPda,
+          providerAuthority: limitedProvider.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([limitedProvider])
+        .rpc();
+
+      const setLimits = (limits: number[], minReputations = [0, 0, 0, 0]) =>
+        program.methods
+          .setWithdrawalLimits(
+            minReputations.map((reputation) => new anchor.BN(reputation)),
+            limits.map((limit) => new anchor.BN(limit)),
+            new anchor.BN(24 * 60 * 60)
+          )
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .rpc();
+
+      const withdraw = (amount: number) =>
+        program.methods
+          .withdrawStake(new anchor.BN(amount), false, null)
+          .accounts({
+            protocol: protocolPda,
+            provider: limitedProviderPda,
+            stakeVault: limitedVaultPda,
+            providerAuthority: limitedProvider.publicKey,
+            authority: limitedProvider.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([limitedProvider])
+          .rpc();
+
+      const limit = 0.1 * LAMPORTS_PER_SOL;
+      await setLimits([limit, limit, limit, limit]);
+
+      await withdraw(limit / 2);
+      try {
+        await withdraw(limit);
+        expect.fail("Should have thrown WithdrawalLimitExceeded error");
+      } catch (error) {
+        expect(error.message).to.include("WithdrawalLimitExceeded");
+      }
+
+      const limitedAccount = await program.account.provider.fetch(limitedProviderPda);
+      expect(limitedAccount.withdrawnThisPeriod.toNumber()).to.equal(limit / 2);
+
+      // Below the lowest tier's minimum the lowest tier's limit still applies
+      const highReputation = 1_000_000_000_000;
+      await setLimits([limit / 4, 0, 0, 0], [highReputation, highReputation, highReputation, highReputation]);
+      expect(limitedAccount.reputationScore.toNumber()).to.be.lessThan(highReputation);
+      try {
+        await withdraw(limit / 2);
+        expect.fail("Should have thrown WithdrawalLimitExceeded error");
+      } catch (error) {
+        expect(error.message).to.include("WithdrawalLimitExceeded");
+      }
+
+      await setLimits([0, 0, 0, 0]);
+    });
+
+    it("Refuses to close a provider with complaint records", async () => {
+      const fresh = await registerFreshProvider("ComplainedAgent");
+      const [complaintPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("complaint"), fresh.providerPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      await program.methods
+        .fileComplaint({ latency: {} }, Array(32).fill(3))
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          complaint: complaintPda,
+          consumer: reporter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+
+      try {
+        await withdrawFrom(fresh, STAKE_AMOUNT, true);
+        expect.fail("Should have thrown ProviderHasHistory error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderHasHistory");
+      }
+
+      // Draining without closing is still allowed
+      await withdrawFrom(fresh, STAKE_AMOUNT);
+      const drained = await program.account.provider.fetch(fresh.providerPda);
+      expect(drained.stakeAmount.toNumber()).to.equal(0);
+      expect(drained.complaints.toNumber()).to.equal(1);
+    });
+
+    it("Closes everything keyed by the provider along with it", async () => {
+      const fresh = await registerFreshProvider("TidyAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const [maintenancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("maintenance"), fresh.providerPda.toBuffer()],
+        program.programId
+      );
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .declareMaintenance(new anchor.BN(now + 3600), new anchor.BN(now + 7200))
+        .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+
+      const periodEnd = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+      const periodStart = periodEnd.subn(3600);
+      const [attestationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("attestation"), fresh.providerPda.toBuffer(), periodStart.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .selfAttest(periodStart, periodEnd, 100, 50, 100)
+        .accounts({
+          provider: fresh.providerPda,
+          attestation: attestationPda,
+          authority: fresh.authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fresh.authority])
+        .rpc();
+
+      const [allowancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("allowance"), fresh.providerPda.toBuffer(), reporter.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .sponsorReporter(reporter.publicKey, new anchor.BN(0.01 * LAMPORTS_PER_SOL))
+        .accounts({
+          provider: fresh.providerPda,
+          allowance: allowancePda,
+          authority: fresh.authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fresh.authority])
+        .rpc();
+      const opened = await program.account.provider.fetch(fresh.providerPda);
+      expect(opened.attestations).to.equal(1);
+      expect(opened.sponsorships).to.equal(1);
+
+      try {
+        await withdrawFrom(fresh, STAKE_AMOUNT, true);
+        expect.fail("Should have thrown ProviderAccountsOpen error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderAccountsOpen");
+      }
+
+      await program.methods
+        .withdrawStake(new anchor.BN(STAKE_AMOUNT), true, null)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          stakeVault: fresh.vaultPda,
+          providerAuthority: fresh.authority.publicKey,
+          authority: fresh.authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: attestationPda, isWritable: true, isSigner: false },
+          { pubkey: allowancePda, isWritable: true, isSigner: false },
+        ])
+        .signers([fresh.authority])
+        .rpc();
+      for (const closed of [fresh.providerPda, freshSlaPda, maintenancePda, attestationPda, allowancePda]) {
+        expect(await provider.connection.getAccountInfo(closed)).to.equal(null);
+      }
+
+      // Re-registering starts clean, so the SLA can be defined again
+      await program.methods
+        .registerProvider("TidyAgent", "https://tidyagent.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          stakeVault: fresh.vaultPda,
+          providerAuthority: fresh.authority.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fresh.authority])
+        .rpc();
+      await defineSlaFor(fresh);
+      expect((await program.account.sla.fetch(freshSlaPda)).isActive).to.equal(true);
+    });
+
+    it("Keeps the vault's rent-exempt reserve when withdrawing the full stake", async () => {
+      const drainingProvider = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        drainingProvider.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [drainingProviderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), drainingProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [drainingVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), drainingProvider.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerProvider("DrainingAgent", "https://draining.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: drainingProviderPda,
+          stakeVault: drainingVaultPda,
+          providerAuthority: drainingProvider.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([drainingProvider])
+        .rpc();
+
+      const rentReserve = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(await provider.connection.getBalance(drainingVaultPda)).to.equal(STAKE_AMOUNT + rentReserve);
+
+      // Withdrawing exactly the stake leaves exactly the reserve behind
+      await program.methods
+        .withdrawStake(new anchor.BN(STAKE_AMOUNT), false, null)
+        .accounts({
+          protocol: protocolPda,
+          provider: drainingProviderPda,
+          stakeVault: drainingVaultPda,
+          providerAuthority: drainingProvider.publicKey,
+          authority: drainingProvider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([drainingProvider])
+        .rpc();
+
+      expect(await provider.connection.getBalance(drainingVaultPda)).to.equal(rentReserve);
+    });
+
+    it("Keeps unvested stake in the vault", async () => {
+      const fresh = await registerFreshProvider("VestingAgent");
+      const locked = 0.3 * LAMPORTS_PER_SOL;
+      await program.methods
+        .setVestingSchedule(new anchor.BN(locked), new anchor.BN(3600), new anchor.BN(7200))
+        .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+
+      // Withdrawing 0.3 SOL would leave 0.2 SOL, less than what is still locked
+      try {
+        await withdrawFrom(fresh, locked);
+        expect.fail("Should have thrown StakeLocked error");
+      } catch (error) {
+        expect(error.message).to.include("StakeLocked");
+      }
+
+      // Anything above the locked amount can still be withdrawn
+      await withdrawFrom(fresh, STAKE_AMOUNT - locked);
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(locked);
+    });
+  });
+
+  describe("Onboarding Approval", () => {
+    const registerPending = async (name: string) => {
+      const applicant = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(applicant.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+
+      const [applicantPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), applicant.publicKey.toBuffer()],
+        program.programId
+      );
+      const [applicantVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), applicant.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerProvider(name, "https://applicant.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")))
+        .accounts({
+          protocol: protocolPda,
+          provider: applicantPda,
+          stakeVault: applicantVaultPda,
+          providerAuthority: applicant.publicKey,
+          nameRecord: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([applicant])
+        .rpc();
+
+      return { applicant, applicantPda, applicantVaultPda };
+    };
+
+    const setMode = (mode: object) =>
+      program.methods
+        .setOnboardingMode(mode as any)
+        .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+        .rpc();
+
+    it("Holds new providers for approval and activates them on approval", async () => {
+      await setMode({ approval: {} });
+      const { applicantPda } = await registerPending("PendingAgent");
+
+      let applicantAccount = await program.account.provider.fetch(applicantPda);
+      expect(applicantAccount.status).to.deep.equal({ pendingApproval: {} });
+
+      await program.methods
+        .approveProvider()
+        .accounts({
+          protocol: protocolPda,
+          provider: applicantPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      applicantAccount = await program.account.provider.fetch(applicantPda);
+      expect(applicantAccount.status).to.deep.equal({ active: {} });
+      await setMode({ open: {} });
+    });
+
+    it("Refunds and closes rejected providers", async () => {
+      await setMode({ approval: {} });
+      const { applicant, applicantPda, applicantVaultPda } = await registerPending("RejectedAgent");
+      const balanceBefore = await provider.connection.getBalance(applicant.publicKey);
+      const vaultBalance = await provider.connection.getBalance(applicantVaultPda);
+
+      await program.methods
+        .rejectProvider()
+        .accounts({
+          protocol: protocolPda,
+          provider: applicantPda,
+          stakeVault: applicantVaultPda,
+          providerAuthority: applicant.publicKey,
+          authority: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      expect(await program.account.provider.fetchNullable(applicantPda)).to.equal(null);
+      expect(await provider.connection.getBalance(applicantVaultPda)).to.equal(0);
+      const balanceAfter = await provider.connection.getBalance(applicant.publicKey);
+      expect(balanceAfter).to.be.greaterThan(balanceBefore + vaultBalance - 1);
+      await setMode({ open: {} });
+    });
+
+    it("Keeps pending providers out of provider hierarchies", async () => {
+      const active = await registerFreshProvider("HierarchyAgent");
+      await setMode({ approval: {} });
+      const { applicant, applicantPda, applicantVaultPda } = await registerPending("PendingChildAgent");
+
+      try {
+        await program.methods
+          .setParentProvider()
+          .accounts({
+            provider: active.providerPda,
+            parentProvider: applicantPda,
+            authority: active.authority.publicKey,
+            parentAuthority: applicant.publicKey,
+          })
+          .signers([active.authority, applicant])
+          .rpc();
+        expect.fail("Should have thrown ProviderInactive error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderInactive");
+      }
+
+      await program.methods
+        .setParentProvider()
+        .accounts({
+          provider: applicantPda,
+          parentProvider: active.providerPda,
+          authority: applicant.publicKey,
+          parentAuthority: active.authority.publicKey,
+        })
+        .signers([applicant, active.authority])
+        .rpc();
+      const reject = () =>
+        program.methods
+          .rejectProvider()
+          .accounts({
+            protocol: protocolPda,
+            provider: applicantPda,
+            stakeVault: applicantVaultPda,
+            providerAuthority: applicant.publicKey,
+            authority: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+      try {
+        await reject();
+        expect.fail("Should have thrown InvalidParent error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidParent");
+      }
+
+      await program.methods
+        .clearParentProvider()
+        .accounts({
+          provider: applicantPda,
+          parentProvider: active.providerPda,
+          authority: applicant.publicKey,
+        })
+        .signers([applicant])
+        .rpc();
+      await reject();
+      expect((await program.account.provider.fetch(active.providerPda)).subProviderCount).to.equal(0);
+      await setMode({ open: {} });
+    });
+
+    it("Refuses to register with an SLA while approval is required", async () => {
+      await setMode({ approval: {} });
+      const applicant = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(applicant.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+      const [applicantPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), applicant.publicKey.toBuffer()],
+        program.programId
+      );
+      const [applicantVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), applicant.publicKey.toBuffer()],
+        program.programId
+      );
+      const [applicantSlaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sla"), applicantPda.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .registerWithSla("BundledApplicant", "https://applicant.ai", 1, new anchor.BN(STAKE_AMOUNT), Array.from(Buffer.from("US")), 99, 1000, 95, 10, new anchor.BN(0))
+          .accounts({
+            register: {
+              protocol: protocolPda,
+              provider: applicantPda,
+              stakeVault: applicantVaultPda,
+              providerAuthority: applicant.publicKey,
+              nameRecord: null,
+              systemProgram: SystemProgram.programId,
+            },
+            sla: applicantSlaPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([applicant])
+          .rpc();
+        expect.fail("Should have thrown ProviderPendingApproval error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderPendingApproval");
+      }
+      expect(await program.account.sla.fetchNullable(applicantSlaPda)).to.equal(null);
+      await setMode({ open: {} });
+    });
+  });
+
+  describe("Snapshot Import", () => {
+    const snapshot = (violations: number, violationCounts: number[]) => ({
+      name: "ImportedAgent",
+      serviceEndpoint: "https://imported.ai",
+      endpointVersion: 1,
+      region: Array.from(Buffer.from("US")),
+      createdAt: new anchor.BN(1_700_000_000),
+      successfulRequests: new anchor.BN(500),
+      violations: new anchor.BN(violations),
+      violationCounts: violationCounts.map((count) => new anchor.BN(count)),
+      lastViolationAt: new anchor.BN(0),
+    });
+
+    const importFor = (
+      authority: Keypair,
+      data: ReturnType<typeof snapshot>,
+      nameRecord: PublicKey | null = null
+    ) => {
+      const [importedPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("provider"), authority.publicKey.toBuffer()],
+        program.programId
+      );
+      const [importedVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), authority.publicKey.toBuffer()],
+        program.programId
+      );
+      return {
+        fresh: { authority, providerPda: importedPda, vaultPda: importedVaultPda },
+        rpc: () =>
+          program.methods
+            .importProviderSnapshot(authority.publicKey, data)
+            .accounts({
+              protocol: protocolPda,
+              provider: importedPda,
+              stakeVault: importedVaultPda,
+              nameRecord,
+              authority: provider.wallet.publicKey,
+              systemProgram: SystemProgram.programId,
+            })
+            .rpc(),
+      };
+    };
+
+    it("Rejects a snapshot whose violation counts don't add up", async () => {
+      const { rpc } = importFor(Keypair.generate(), snapshot(3, [1, 0, 0, 0, 0]));
+      try {
+        await rpc();
+        expect.fail("Should have thrown InvalidSnapshot error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidSnapshot");
+      }
+
+      // Counts that overflow when summed don't wrap around to match
+      const overflowing = {
+        ...snapshot(0, [0, 0, 0, 0, 0]),
+        violationCounts: ["18446744073709551615", "1", "0", "0", "0"].map((count) => new anchor.BN(count)),
+      };
+      try {
+        await importFor(Keypair.generate(), overflowing).rpc();
+        expect.fail("Should have thrown InvalidSnapshot error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidSnapshot");
+      }
+    });
+
+    it("Claims an imported name while unique names are enforced", async () => {
+      const key = Buffer.alloc(64);
+      Buffer.from("importedagent").copy(key);
+      const [nameRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("name"), key.subarray(0, 32), key.subarray(32)],
+        program.programId
+      );
+      const setUniqueNames = (enabled: boolean) =>
+        program.methods
+          .setUniqueNames(enabled)
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .rpc();
+
+      await setUniqueNames(true);
+      try {
+        await importFor(Keypair.generate(), snapshot(0, [0, 0, 0, 0, 0])).rpc();
+        expect.fail("Should have thrown NameRecordRequired error");
+      } catch (error) {
+        expect(error.message).to.include("NameRecordRequired");
+      }
+
+      const holder = Keypair.generate();
+      await importFor(holder, snapshot(0, [0, 0, 0, 0, 0]), nameRecordPda).rpc();
+      const record = await program.account.nameRecord.fetch(nameRecordPda);
+      // The name belongs to the imported provider's authority, who can later release it
+      expect(record.authority.toBase58()).to.equal(holder.publicKey.toBase58());
+
+      try {
+        await importFor(Keypair.generate(), snapshot(0, [0, 0, 0, 0, 0]), nameRecordPda).rpc();
+        expect.fail("Should have thrown NameTaken error");
+      } catch (error) {
+        expect(error.message).to.include("NameTaken");
+      }
+
+      await setUniqueNames(false);
+    });
+
+    it("Imports a provider that can be topped up and fully withdrawn", async () => {
+      const authority = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(authority.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+
+      const providerCount = async () =>
+        (await program.account.protocol.fetch(protocolPda)).totalProviders.toNumber();
+      const countBefore = await providerCount();
+
+      const { fresh, rpc } = importFor(authority, snapshot(0, [0, 0, 0, 0, 0]));
+      await rpc();
+      // An import holds no stake, so it isn't counted until it's topped up
+      expect(await providerCount()).to.equal(countBefore);
+
+      const rentReserve = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(await provider.connection.getBalance(fresh.vaultPda)).to.equal(rentReserve);
+      const imported = await program.account.provider.fetch(fresh.providerPda);
+      expect(imported.imported).to.equal(true);
+      expect(imported.successfulRequests.toNumber()).to.equal(500);
+
+      const [fundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), fresh.providerPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .configureAutoTopup(new anchor.BN(STAKE_AMOUNT))
+        .accounts({ provider: fresh.providerPda, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: fundingPda,
+            lamports: STAKE_AMOUNT + rentReserve,
+          })
+        )
+      );
+      await program.methods
+        .triggerTopup()
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          fundingSource: fundingPda,
+          stakeVault: fresh.vaultPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const toppedUp = await program.account.provider.fetch(fresh.providerPda);
+      expect(toppedUp.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+      expect(toppedUp.status).to.deep.equal({ active: {} });
+      expect(await providerCount()).to.equal(countBefore + 1);
+
+      await withdrawFrom(fresh, STAKE_AMOUNT);
+      expect(await provider.connection.getBalance(fresh.vaultPda)).to.equal(rentReserve);
+      expect(await providerCount()).to.equal(countBefore);
+    });
+
+    it("Fades each violation's weight with its age", async () => {
+      const decaySeconds = 10_000_000;
+      await program.methods
+        .setViolationDecay(new anchor.BN(decaySeconds))
+        .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+        .rpc();
+      const weight = (await program.account.protocol.fetch(protocolPda)).violationWeights[0];
+      const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+
+      // Two uptime violations aged so that each still carries the given weight (bps); the
+      // margins keep the expected weight exact for the few seconds before the import lands
+      const reputationAt = async (lastViolationAt: number) => {
+        const { fresh, rpc } = importFor(Keypair.generate(), {
+          ...snapshot(2, [2, 0, 0, 0, 0]),
+          lastViolationAt: new anchor.BN(lastViolationAt),
+        });
+        await rpc();
+        return (await program.account.provider.fetch(fresh.providerPda)).reputationScore.toNumber();
+      };
+      const expected = (weightBps: number) =>
+        Math.floor((500 * REPUTATION_SCALE * 10_000) / (2 * weight * weightBps + 10_000));
+
+      // Half-way through the window each violation weighs half
+      expect(await reputationAt(now - decaySeconds / 2 + 500)).to.equal(expected(5_000));
+      // Just inside the window a sliver of weight is left
+      expect(await reputationAt(now - decaySeconds + 1_500)).to.equal(expected(1));
+      // Past the window the violations no longer count
+      expect(await reputationAt(now - decaySeconds - 100)).to.equal(500 * REPUTATION_SCALE);
+
+      await program.methods
+        .setViolationDecay(new anchor.BN(0))
+        .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+        .rpc();
+      // Without decay they count in full
+      expect(await reputationAt(now - decaySeconds - 100)).to.equal(expected(10_000));
+    });
+  });
+
+  describe("Slashing Pipeline", () => {
+    const configure = (method: any) =>
+      method.accounts({ protocol: protocolPda, authority: provider.wallet.publicKey }).rpc();
+
+    it("Slashes an older violation after a newer one was filed", async () => {
+      const fresh = await registerFreshProvider("BacklogAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const older = await reportAgainst(fresh, freshSlaPda, 1);
+      const newer = await reportAgainst(fresh, freshSlaPda, 2);
+
+      await slashFor(fresh, freshSlaPda, older);
+
+      expect((await program.account.violation.fetch(older)).isResolved).to.equal(true);
+      expect((await program.account.violation.fetch(newer)).isResolved).to.equal(false);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT * 0.9);
+      expect(providerAccount.pendingViolations.toNumber()).to.equal(1);
+    });
+
+    it("Lets a provider deactivated by a slash withdraw its remainder once nothing is pending", async () => {
+      const fresh = await registerFreshProvider("DeactivatedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 90);
+      const first = await reportAgainst(fresh, freshSlaPda, 1);
+      const second = await reportAgainst(fresh, freshSlaPda, 2);
+
+      await slashFor(fresh, freshSlaPda, first);
+      let providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.status).to.deep.equal({ inactive: {} });
+
+      try {
+        await withdrawFrom(fresh, providerAccount.stakeAmount.toNumber());
+        expect.fail("Should have thrown PendingViolations error");
+      } catch (error) {
+        expect(error.message).to.include("PendingViolations");
+      }
+
+      await slashFor(fresh, freshSlaPda, second);
+      providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      const remainder = providerAccount.stakeAmount.toNumber();
+      expect(remainder).to.be.greaterThan(0);
+
+      const before = await provider.connection.getBalance(fresh.authority.publicKey);
+      await withdrawFrom(fresh, remainder);
+      const after = await provider.connection.getBalance(fresh.authority.publicKey);
+      expect(after - before).to.equal(remainder);
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(0);
+    });
+
+    it("Holds a banned provider's stake until its violations are resolved", async () => {
+      const fresh = await registerFreshProvider("BannedExitAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+      await program.methods
+        .blacklistProvider()
+        .accounts({ protocol: protocolPda, provider: fresh.providerPda, authority: provider.wallet.publicKey })
+        .rpc();
+
+      try {
+        await withdrawFrom(fresh, STAKE_AMOUNT);
+        expect.fail("Should have thrown PendingViolations error");
+      } catch (error) {
+        expect(error.message).to.include("PendingViolations");
+      }
+
+      await slashFor(fresh, freshSlaPda, violation);
+      const remainder = (await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber();
+      await withdrawFrom(fresh, remainder);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.stakeAmount.toNumber()).to.equal(0);
+      expect(providerAccount.status).to.deep.equal({ banned: {} });
+    });
+
+    it("Halts slashes and settlements while slashing is frozen", async () => {
+      const fresh = await registerFreshProvider("FrozenAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+
+      await configure(program.methods.freezeSlashing());
+      try {
+        await slashFor(fresh, freshSlaPda, violation);
+        expect.fail("Should have thrown SlashingFrozen error");
+      } catch (error) {
+        expect(error.message).to.include("SlashingFrozen");
+      }
+      try {
+        await program.methods
+          .acknowledgeViolation(Buffer.from([]))
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            sla: freshSlaPda,
+            violation,
+            stakeVault: fresh.vaultPda,
+            reporter: reporter.publicKey,
+            parentProvider: null,
+            authority: fresh.authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([fresh.authority])
+          .rpc();
+        expect.fail("Should have thrown SlashingFrozen error");
+      } catch (error) {
+        expect(error.message).to.include("SlashingFrozen");
+      }
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+
+      await configure(program.methods.unfreezeSlashing());
+      await slashFor(fresh, freshSlaPda, violation);
+      expect((await program.account.violation.fetch(violation)).isResolved).to.equal(true);
+    });
+
+    it("Restores probation when a slashed provider is unbanned", async () => {
+      const fresh = await registerFreshProvider("RestoredAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      await slashFor(fresh, freshSlaPda, await reportAgainst(fresh, freshSlaPda, 1));
+      expect((await program.account.provider.fetch(fresh.providerPda)).status).to.deep.equal({ probation: {} });
+
+      const banAccounts = { protocol: protocolPda, provider: fresh.providerPda, authority: provider.wallet.publicKey };
+      await program.methods.blacklistProvider().accounts(banAccounts).rpc();
+      await program.methods.unblacklistProvider().accounts(banAccounts).rpc();
+
+      expect((await program.account.provider.fetch(fresh.providerPda)).status).to.deep.equal({ probation: {} });
+    });
+
+    it("Rejects dust slashes against providers above the minimum stake", async () => {
+      const fresh = await registerFreshProvider("DustAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const dust = await reportAgainst(fresh, freshSlaPda, 1);
+
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0.1 * LAMPORTS_PER_SOL)));
+      try {
+        await slashFor(fresh, freshSlaPda, dust);
+        expect.fail("Should have thrown SlashTooSmall error");
+      } catch (error) {
+        expect(error.message).to.include("SlashTooSmall");
+      }
+
+      // A slash at or above the minimum goes through
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0.05 * LAMPORTS_PER_SOL)));
+      await slashFor(fresh, freshSlaPda, dust);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.stakeAmount.toNumber()).to.equal(STAKE_AMOUNT - 0.05 * LAMPORTS_PER_SOL);
+
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0)));
+    });
+
+    it("Keeps a minimum-slash bump within the single-slash ceiling", async () => {
+      const fresh = await registerFreshProvider("BumpedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 90);
+      const first = await reportAgainst(fresh, freshSlaPda, 1);
+      const second = await reportAgainst(fresh, freshSlaPda, 2);
+
+      // Leaves 0.05 SOL, below the minimum stake
+      await slashFor(fresh, freshSlaPda, first);
+      const remainder = (await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber();
+      expect(remainder).to.equal(0.05 * LAMPORTS_PER_SOL);
+
+      // 90% of the remainder is below the minimum slash, so it's bumped to the whole
+      // remainder, but the 50% ceiling still caps it
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0.1 * LAMPORTS_PER_SOL)));
+      await configure(program.methods.setMaxSingleSlash(5000));
+      await slashFor(fresh, freshSlaPda, second);
+
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.stakeAmount.toNumber()).to.equal(remainder / 2);
+
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0)));
+      await configure(program.methods.setMaxSingleSlash(10000));
+    });
+
+    it("Prices max_recoverable the way slash charges it", async () => {
+      const fresh = await registerFreshProvider("PricedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+      const price = (report: PublicKey | null) =>
+        program.methods
+          .maxRecoverable()
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            sla: freshSlaPda,
+            violation: report,
+            attestation: null,
+          })
+          .view();
+
+      // Below the minimum slash a report recovers nothing, since slash would reject it
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0.5 * LAMPORTS_PER_SOL)));
+      expect((await price(null)).toNumber()).to.equal(0);
+      expect((await price(violation)).toNumber()).to.equal(0);
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0)));
+
+      const quoted = (await price(violation)).toNumber();
+      expect(quoted).to.be.greaterThan(0);
+      await slashFor(fresh, freshSlaPda, violation);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(STAKE_AMOUNT - providerAccount.stakeAmount.toNumber()).to.equal(quoted);
+
+      // A resolved report can't be slashed again
+      expect((await price(violation)).toNumber()).to.equal(0);
+    });
+
+    it("Refuses to top up a banned provider", async () => {
+      const fresh = await registerFreshProvider("BannedTopupAgent");
+      const [fundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), fresh.providerPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .configureAutoTopup(new anchor.BN(2 * STAKE_AMOUNT))
+        .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: fundingPda,
+            lamports: STAKE_AMOUNT,
+          })
+        )
+      );
+      await program.methods
+        .blacklistProvider()
+        .accounts({ protocol: protocolPda, provider: fresh.providerPda, authority: provider.wallet.publicKey })
+        .rpc();
+
+      try {
+        await program.methods
+          .triggerTopup()
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            fundingSource: fundingPda,
+            stakeVault: fresh.vaultPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown ProviderBlacklisted error");
+      } catch (error) {
+        expect(error.message).to.include("ProviderBlacklisted");
+      }
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+    });
+
+    it("Tops up only after a slash and hands the funding source back to its authority", async () => {
+      const fresh = await registerFreshProvider("FundedAgent");
+      const [fundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), fresh.providerPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .configureAutoTopup(new anchor.BN(2 * STAKE_AMOUNT))
+        .accounts({ provider: fresh.providerPda, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: fundingPda,
+            lamports: 0.3 * LAMPORTS_PER_SOL,
+          })
+        )
+      );
+      const trigger = () =>
+        program.methods
+          .triggerTopup()
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            fundingSource: fundingPda,
+            stakeVault: fresh.vaultPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+
+      // Below target, but nothing was slashed
+      try {
+        await trigger();
+        expect.fail("Should have thrown TopUpUnavailable error");
+      } catch (error) {
+        expect(error.message).to.include("TopUpUnavailable");
+      }
+      await configure(program.methods.pauseProtocol());
+      try {
+        await trigger();
+        expect.fail("Should have thrown ProtocolPaused error");
+      } catch (error) {
+        expect(error.message).to.include("ProtocolPaused");
+      }
+      await configure(program.methods.unpauseProtocol());
+
+      const withdrawFunding = (amount: number) =>
+        program.methods
+          .withdrawFunding(new anchor.BN(amount))
+          .accounts({
+            provider: fresh.providerPda,
+            fundingSource: fundingPda,
+            authority: fresh.authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([fresh.authority])
+          .rpc();
+      const funded = await provider.connection.getBalance(fundingPda);
+      try {
+        await withdrawFunding(funded - 1);
+        expect.fail("Should have thrown FundingRentReserve error");
+      } catch (error) {
+        expect(error.message).to.include("FundingRentReserve");
+      }
+      await withdrawFunding(0.1 * LAMPORTS_PER_SOL);
+      expect(await provider.connection.getBalance(fundingPda)).to.equal(funded - 0.1 * LAMPORTS_PER_SOL);
+
+      // Closing the provider empties what's left back to its authority
+      await withdrawFrom(fresh, STAKE_AMOUNT, true);
+      expect(await provider.connection.getBalance(fundingPda)).to.equal(0);
+    });
+
+    it("Refunds a sponsored report's rent to the sponsoring provider on expiry", async () => {
+      const fresh = await registerFreshProvider("SponsorAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const [allowancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("allowance"), fresh.providerPda.toBuffer(), reporter.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .sponsorReporter(reporter.publicKey, new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          provider: fresh.providerPda,
+          allowance: allowancePda,
+          authority: fresh.authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fresh.authority])
+        .rpc();
+
+      const [violation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), fresh.providerPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const occurredAt = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(1)));
+      await program.methods
+        .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, 1)), "Outage", null, Buffer.from([]), occurredAt, occurredAt)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          sla: freshSlaPda,
+          violation,
+          reporter: reporter.publicKey,
+          rentSponsor: null,
+          reporterAllowance: allowancePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reporter])
+        .rpc();
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(30 * 24 * 60 * 60)));
+      expect((await program.account.violation.fetch(violation)).rentPayer.toString()).to.equal(
+        fresh.authority.publicKey.toString()
+      );
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      const rent = await provider.connection.getBalance(violation);
+      const reporterBefore = await provider.connection.getBalance(reporter.publicKey);
+      const sponsorBefore = await provider.connection.getBalance(fresh.authority.publicKey);
+      await program.methods
+        .expireViolation()
+        .accounts({
+          provider: fresh.providerPda,
+          violation,
+          rentPayer: fresh.authority.publicKey,
+          cranker: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      expect(await provider.connection.getBalance(reporter.publicKey)).to.equal(reporterBefore);
+      expect((await provider.connection.getBalance(fresh.authority.publicKey)) - sponsorBefore).to.equal(rent);
+    });
+
+    it("Holds an SLA's terms until the lock chosen at definition expires", async () => {
+      const fresh = await registerFreshProvider("LockedSlaAgent");
+      const lockedUntil = Math.floor(Date.now() / 1000) + 3600;
+      const freshSlaPda = await defineSlaFor(fresh, 20, lockedUntil);
+      expect((await program.account.sla.fetch(freshSlaPda)).lockedUntil.toNumber()).to.equal(lockedUntil);
+
+      for (const method of [
+        program.methods.increasePenalty(30),
+        program.methods.decreasePenalty(10),
+        program.methods.deactivateSla(),
+      ]) {
+        try {
+          await method
+            .accounts({
+              protocol: protocolPda,
+              provider: fresh.providerPda,
+              sla: freshSlaPda,
+              authority: fresh.authority.publicKey,
+            })
+            .signers([fresh.authority])
+            .rpc();
+          expect.fail("Should have thrown SLALocked error");
+        } catch (error) {
+          expect(error.message).to.include("SLALocked");
+        }
+      }
+      const sla = await program.account.sla.fetch(freshSlaPda);
+      expect(sla.penaltyPercentage).to.equal(20);
+      expect(sla.isActive).to.equal(true);
+    });
+
+    it("Keeps breaches from an SLA's active period reportable after it is deactivated", async () => {
+      const fresh = await registerFreshProvider("RetiredSlaAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const slaUpdate = (method: any) =>
+        method
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            sla: freshSlaPda,
+            authority: fresh.authority.publicKey,
+          })
+          .signers([fresh.authority])
+          .rpc();
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await slaUpdate(program.methods.deactivateSla());
+      const sla = await program.account.sla.fetch(freshSlaPda);
+      expect(sla.isActive).to.equal(false);
+      expect(sla.deactivatedAt.toNumber()).to.be.greaterThan(sla.createdAt.toNumber());
+
+      // A breach from while the SLA was active is still reportable
+      const violation = await reportAgainst(fresh, freshSlaPda);
+      expect((await program.account.violation.fetch(violation)).sla.toString()).to.equal(freshSlaPda.toString());
+
+      // One from after it was deactivated is not
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      const [lateViolation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), fresh.providerPda.toBuffer(), providerAccount.violations.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      try {
+        await program.methods
+          .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, 2)), "Outage", null, Buffer.from([]), sla.deactivatedAt, sla.deactivatedAt)
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            sla: freshSlaPda,
+            violation: lateViolation,
+            reporter: reporter.publicKey,
+            rentSponsor: null,
+            reporterAllowance: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([reporter])
+          .rpc();
+        expect.fail("Should have thrown OutsideSLAWindow error");
+      } catch (error) {
+        expect(error.message).to.include("OutsideSLAWindow");
+      }
+
+      try {
+        await slaUpdate(program.methods.increasePenalty(20));
+        expect.fail("Should have thrown NoActiveSLA error");
+      } catch (error) {
+        expect(error.message).to.include("NoActiveSLA");
+      }
+    });
+
+    it("Settles a violation only under the SLA it was reported against", async () => {
+      const parent = await registerFreshProvider("LenientParentAgent");
+      const parentSlaPda = await defineSlaFor(parent, 5);
+      const sub = await registerFreshProvider("StrictSubAgent");
+      const subSlaPda = await defineSlaFor(sub, 50);
+      await program.methods
+        .setParentProvider()
+        .accounts({
+          provider: sub.providerPda,
+          parentProvider: parent.providerPda,
+          authority: sub.authority.publicKey,
+          parentAuthority: parent.authority.publicKey,
+        })
+        .signers([sub.authority, parent.authority])
+        .rpc();
+
+      const violation = await reportAgainst(sub, parentSlaPda);
+      expect((await program.account.violation.fetch(violation)).sla.toString()).to.equal(parentSlaPda.toString());
+
+      try {
+        await slashFor(sub, subSlaPda, violation);
+        expect.fail("Should have thrown SLAMismatch error");
+      } catch (error) {
+        expect(error.message).to.include("SLAMismatch");
+      }
+    });
+
+    it("Lists the SLAs a sub-provider can be held to", async () => {
+      const parent = await registerFreshProvider("ListedParentAgent");
+      const parentSlaPda = await defineSlaFor(parent, 5);
+      const sub = await registerFreshProvider("ListedSubAgent");
+      const subSlaPda = await defineSlaFor(sub, 50, 4_000_000_000);
+      const stranger = await registerFreshProvider("UnlistedAgent");
+      const strangerSlaPda = await defineSlaFor(stranger);
+      await program.methods
+        .setParentProvider()
+        .accounts({
+          provider: sub.providerPda,
+          parentProvider: parent.providerPda,
+          authority: sub.authority.publicKey,
+          parentAuthority: parent.authority.publicKey,
+        })
+        .signers([sub.authority, parent.authority])
+        .rpc();
+      const getSlas = (slas: PublicKey[]) =>
+        program.methods
+          .getSlas()
+          .accounts({ provider: sub.providerPda })
+          .remainingAccounts(slas.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })));
+
+      const summaries = await getSlas([subSlaPda, parentSlaPda]).view();
+      expect(summaries.map((summary) => summary.sla.toString())).to.deep.equal([
+        subSlaPda.toString(),
+        parentSlaPda.toString(),
+      ]);
+      expect(summaries.map((summary) => summary.penaltyPercentage)).to.deep.equal([50, 5]);
+      expect(summaries[0].isActive).to.equal(true);
+      expect(summaries[0].lockedUntil.toNumber()).to.equal(4_000_000_000);
+
+      try {
+        await getSlas([subSlaPda, strangerSlaPda]).rpc();
+        expect.fail("Should have thrown SLAMismatch error");
+      } catch (error) {
+        expect(error.message).to.include("SLAMismatch");
+      }
+    });
+
+    it("Slashes a sub-provider under its parent's SLA without touching the parent's stake", async () => {
+      const parent = await registerFreshProvider("BondedParentAgent");
+      const parentSlaPda = await defineSlaFor(parent);
+      const sub = await registerFreshProvider("BondedSubAgent");
+      await program.methods
+        .setParentProvider()
+        .accounts({
+          provider: sub.providerPda,
+          parentProvider: parent.providerPda,
+          authority: sub.authority.publicKey,
+          parentAuthority: parent.authority.publicKey,
+        })
+        .signers([sub.authority, parent.authority])
+        .rpc();
+      const violation = await reportAgainst(sub, parentSlaPda);
+      const slashFrom = (stakeVault: PublicKey) =>
+        program.methods
+          .slash()
+          .accounts({
+            protocol: protocolPda,
+            provider: sub.providerPda,
+            sla: parentSlaPda,
+            violation,
+            stakeVault,
+            reporter: reporter.publicKey,
+            parentProvider: parent.providerPda,
+            attestation: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([reporter])
+          .rpc();
+
+      // The parent's vault can't stand in for the violating provider's
+      try {
+        await slashFrom(parent.vaultPda);
+        expect.fail("Should have thrown ConstraintSeeds error");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintSeeds");
+      }
+
+      const parentVaultBefore = await provider.connection.getBalance(parent.vaultPda);
+      const subVaultBefore = await provider.connection.getBalance(sub.vaultPda);
+      await slashFrom(sub.vaultPda);
+
+      expect(await provider.connection.getBalance(parent.vaultPda)).to.equal(parentVaultBefore);
+      expect((await program.account.provider.fetch(parent.providerPda)).stakeAmount.toNumber()).to.equal(STAKE_AMOUNT);
+      expect(subVaultBefore - (await provider.connection.getBalance(sub.vaultPda))).to.equal(STAKE_AMOUNT * 0.1);
+    });
+
+    it("Lets a sponsored reporter with no lamports of its own file a report", async () => {
+      const fresh = await registerFreshProvider("PatronAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const monitor = Keypair.generate();
+      const [allowancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("allowance"), fresh.providerPda.toBuffer(), monitor.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .sponsorReporter(monitor.publicKey, new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          provider: fresh.providerPda,
+          allowance: allowancePda,
+          authority: fresh.authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fresh.authority])
+        .rpc();
+
+      const fee = 0.001 * LAMPORTS_PER_SOL;
+      await configure(program.methods.setReportFee(new anchor.BN(fee)));
+      const [violation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), fresh.providerPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const occurredAt = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+      expect(await provider.connection.getBalance(monitor.publicKey)).to.equal(0);
+      await program.methods
+        .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, 1)), "Outage", null, Buffer.from([]), occurredAt, occurredAt)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          sla: freshSlaPda,
+          violation,
+          reporter: monitor.publicKey,
+          rentSponsor: null,
+          reporterAllowance: allowancePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([monitor])
+        .rpc();
+      await configure(program.methods.setReportFee(new anchor.BN(0)));
+
+      expect(await provider.connection.getBalance(monitor.publicKey)).to.equal(0);
+      const rent = await provider.connection.getBalance(violation);
+      const allowance = await program.account.reporterAllowance.fetch(allowancePda);
+      expect(allowance.spent.toNumber()).to.equal(rent + fee);
+      const violationAccount = await program.account.violation.fetch(violation);
+      expect(violationAccount.reporter.toString()).to.equal(monitor.publicKey.toString());
+      expect(violationAccount.rentPayer.toString()).to.equal(fresh.authority.publicKey.toString());
+    });
+
+    it("Rejects reports from the provider's own authority or organization", async () => {
+      const fresh = await registerFreshProvider("SelfDealingAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const orgAuthority = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(orgAuthority.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+      const [organizationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("organization"), orgAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .createOrganization("Self Dealing Fleet")
+        .accounts({
+          organization: organizationPda,
+          authority: orgAuthority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([orgAuthority])
+        .rpc();
+      await program.methods
+        .joinOrganization()
+        .accounts({
+          provider: fresh.providerPda,
+          organization: organizationPda,
+          authority: fresh.authority.publicKey,
+          organizationAuthority: orgAuthority.publicKey,
+        })
+        .signers([fresh.authority, orgAuthority])
+        .rpc();
+
+      const [violation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("violation"), fresh.providerPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const occurredAt = (await program.account.sla.fetch(freshSlaPda)).createdAt;
+      const reportAs = (signer: Keypair) =>
+        program.methods
+          .reportViolation({ uptimeViolation: {} }, Array.from(Buffer.alloc(32, 1)), "Outage", null, Buffer.from([]), occurredAt, occurredAt)
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            sla: freshSlaPda,
+            violation,
+            reporter: signer.publicKey,
+            rentSponsor: null,
+            reporterAllowance: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([signer])
+          .rpc();
+
+      for (const signer of [fresh.authority, orgAuthority]) {
+        try {
+          await reportAs(signer);
+          expect.fail("Should have thrown SelfDealing error");
+        } catch (error) {
+          expect(error.message).to.include("SelfDealing");
+        }
+      }
+
+      // An unrelated reporter can still report
+      await reportAs(reporter);
+      expect((await program.account.violation.fetch(violation)).reporter.toString()).to.equal(
+        reporter.publicKey.toString()
+      );
+    });
+
+    it("Caps the report fee and pays it into the treasury", async () => {
+      try {
+        await configure(program.methods.setReportFee(new anchor.BN(0.02 * LAMPORTS_PER_SOL)));
+        expect.fail("Should have thrown ReportFeeTooHigh error");
+      } catch (error) {
+        expect(error.message).to.include("ReportFeeTooHigh");
+      }
+
+      const fee = 0.001 * LAMPORTS_PER_SOL;
+      await configure(program.methods.setReportFee(new anchor.BN(fee)));
+      const fresh = await registerFreshProvider("FeeAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const [treasuryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury")],
+        program.programId
+      );
+      const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+      await reportAgainst(fresh, freshSlaPda, 1);
+      expect((await provider.connection.getBalance(treasuryPda)) - treasuryBefore).to.equal(fee);
+
+      await configure(program.methods.setReportFee(new anchor.BN(0)));
+    });
+
+    it("Gives each violation type its own cure window", async () => {
+      try {
+        await configure(program.methods.setCureWindowForType({ uptimeViolation: {} }, new anchor.BN(-1)));
+        expect.fail("Should have thrown InvalidCureWindow error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidCureWindow");
+      }
+
+      await configure(program.methods.setCureWindowForType({ accuracyViolation: {} }, new anchor.BN(3 * 86400)));
+      await configure(program.methods.setCureWindowForType({ uptimeViolation: {} }, new anchor.BN(3600)));
+      const fresh = await registerFreshProvider("CureWindowAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const violation = await program.account.violation.fetch(await reportAgainst(fresh, freshSlaPda, 1));
+      expect(violation.cureDeadline.toNumber() - violation.timestamp.toNumber()).to.equal(3600);
+
+      // Unset types fall back to the global window (off here)
+      await configure(program.methods.setCureWindowForType({ uptimeViolation: {} }, new anchor.BN(0)));
+      const fallback = await program.account.violation.fetch(await reportAgainst(fresh, freshSlaPda, 2));
+      expect(fallback.cureDeadline.toNumber()).to.equal(fallback.timestamp.toNumber());
+
+      await configure(program.methods.setCureWindowForType({ accuracyViolation: {} }, new anchor.BN(0)));
+    });
+
+    it("Shortens the cure window of a provider that keeps losing cure reviews", async () => {
+      await configure(program.methods.setCureWindow(new anchor.BN(3600)));
+      await configure(program.methods.setCureEscalation(new anchor.BN(1)));
+      const fresh = await registerFreshProvider("DisputingAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const cureWindowOf = async (violation: PublicKey) => {
+        const account = await program.account.violation.fetch(violation);
+        return account.cureDeadline.toNumber() - account.timestamp.toNumber();
+      };
+
+      const disputed = await reportAgainst(fresh, freshSlaPda, 1);
+      expect(await cureWindowOf(disputed)).to.equal(3600);
+      await program.methods
+        .cureViolation(Array.from(Buffer.alloc(32, 9)))
+        .accounts({ provider: fresh.providerPda, violation: disputed, authority: fresh.authority.publicKey })
+        .signers([fresh.authority])
+        .rpc();
+      await program.methods
+        .reviewCure(false)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          violation: disputed,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      expect((await program.account.provider.fetch(fresh.providerPda)).disputesLost.toNumber()).to.equal(1);
+
+      expect(await cureWindowOf(await reportAgainst(fresh, freshSlaPda, 2))).to.equal(1800);
+
+      await configure(program.methods.setCureEscalation(new anchor.BN(0)));
+      await configure(program.methods.setCureWindow(new anchor.BN(0)));
+    });
+
+    it("Refunds part of the report fee when a violation is dismissed in good faith", async () => {
+      try {
+        await configure(program.methods.setDismissalRefund(10_001));
+        expect.fail("Should have thrown InvalidBasisPoints error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBasisPoints");
+      }
+
+      const fee = 0.001 * LAMPORTS_PER_SOL;
+      await configure(program.methods.setReportFee(new anchor.BN(fee)));
+      await configure(program.methods.setDismissalRefund(5000));
+      const fresh = await registerFreshProvider("DismissedAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      const firstViolation = await reportAgainst(fresh, freshSlaPda, 1);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(
+        (await provider.connection.getAccountInfo(firstViolation)).data.length
+      );
+      const dismiss = (violation: PublicKey, malicious: boolean, authority: Keypair | null = null) =>
+        program.methods
+          .dismissViolation(malicious)
+          .accounts({
+            protocol: protocolPda,
+            provider: fresh.providerPda,
+            violation,
+            rentPayer: reporter.publicKey,
+            feePayer: reporter.publicKey,
+            authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          })
+          .signers(authority ? [authority] : [])
+          .rpc();
+      try {
+        await dismiss(firstViolation, false, fresh.authority);
+        expect.fail("Should have thrown Unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      let before = await provider.connection.getBalance(reporter.publicKey);
+      await dismiss(firstViolation, false);
+      expect((await provider.connection.getBalance(reporter.publicKey)) - before).to.equal(rent + fee / 2);
+      const providerAccount = await program.account.provider.fetch(fresh.providerPda);
+      expect(providerAccount.pendingViolations.toNumber()).to.equal(0);
+      expect(providerAccount.dismissedViolations.toNumber()).to.equal(1);
+
+      // A malicious report only gets its rent back
+      const second = await reportAgainst(fresh, freshSlaPda, 2);
+      before = await provider.connection.getBalance(reporter.publicKey);
+      await dismiss(second, true);
+      expect((await provider.connection.getBalance(reporter.publicKey)) - before).to.equal(rent);
+
+      await configure(program.methods.setDismissalRefund(0));
+      await configure(program.methods.setReportFee(new anchor.BN(0)));
+    });
+
+    it("Sweeps lamports stranded in closed providers' PDAs into the treasury", async () => {
+      const [treasuryPda] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+      const closed = await registerFreshProvider("SweptAgent");
+      await withdrawFrom(closed, STAKE_AMOUNT, true);
+      const [fundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), closed.providerPda.toBuffer()],
+        program.programId
+      );
+      const stranded = 0.01 * LAMPORTS_PER_SOL;
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({ fromPubkey: provider.wallet.publicKey, toPubkey: closed.vaultPda, lamports: stranded }),
+          SystemProgram.transfer({ fromPubkey: provider.wallet.publicKey, toPubkey: fundingPda, lamports: stranded })
+        )
+      );
+      const sweep = (fresh: FreshProvider, funding: PublicKey) =>
+        program.methods
+          .sweepToTreasury([fresh.authority.publicKey])
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .remainingAccounts(
+            [fresh.providerPda, fresh.vaultPda, funding].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+          )
+          .rpc();
+
+      // A registered provider's PDAs are still its own
+      const open = await registerFreshProvider("UnsweptAgent");
+      const [openFundingPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("funding"), open.providerPda.toBuffer()],
+        program.programId
+      );
+      try {
+        await sweep(open, openFundingPda);
+        expect.fail("Should have thrown InvalidSweepSource error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidSweepSource");
+      }
+
+      const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+      await sweep(closed, fundingPda);
+      expect((await provider.connection.getBalance(treasuryPda)) - treasuryBefore).to.equal(2 * stranded);
+      expect(await provider.connection.getBalance(closed.vaultPda)).to.equal(0);
+      expect(await provider.connection.getBalance(fundingPda)).to.equal(0);
+    });
+
+    it("Tracks each reporter's confirmed and dismissed reports", async () => {
+      const [recordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("reporter"), reporter.publicKey.toBuffer()],
+        program.programId
+      );
+      const before = await program.account.reporterRecord.fetch(recordPda);
+
+      const fresh = await registerFreshProvider("TrackedAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      await slashFor(fresh, freshSlaPda, await reportAgainst(fresh, freshSlaPda, 1));
+
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(1)));
+      const stale = await reportAgainst(fresh, freshSlaPda, 2);
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(30 * 24 * 60 * 60)));
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await program.methods
+        .expireViolation()
+        .accounts({
+          provider: fresh.providerPda,
+          violation: stale,
+          rentPayer: reporter.publicKey,
+          cranker: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const record = await program.account.reporterRecord.fetch(recordPda);
+      expect(record.reporter.toString()).to.equal(reporter.publicKey.toString());
+      expect(record.confirmedReports.toNumber()).to.equal(before.confirmedReports.toNumber() + 1);
+      expect(record.dismissedReports.toNumber()).to.equal(before.dismissedReports.toNumber() + 1);
+
+      const accuracy = await program.methods
+        .reporterAccuracy()
+        .accounts({ reporterRecord: recordPda })
+        .view();
+      const confirmed = record.confirmedReports.toNumber();
+      expect(accuracy.toNumber()).to.equal(
+        Math.floor((confirmed * 10_000) / (confirmed + record.dismissedReports.toNumber()))
+      );
+    });
+
+    it("Restores the provider's reputation when an unconfirmed report expires", async () => {
+      const fresh = await registerFreshProvider("GriefedAgent");
+      const freshSlaPda = await defineSlaFor(fresh);
+      await program.methods
+        .recordSuccess(null)
+        .accounts({
+          protocol: protocolPda,
+          provider: fresh.providerPda,
+          caller: provider.wallet.publicKey,
+        })
+        .rpc();
+      const before = await program.account.provider.fetch(fresh.providerPda);
+
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(1)));
+      const stale = await reportAgainst(fresh, freshSlaPda);
+      await configure(program.methods.setEvidenceTtl(new anchor.BN(30 * 24 * 60 * 60)));
+      const reported = await program.account.provider.fetch(fresh.providerPda);
+      expect(reported.reputationScore.toNumber()).to.be.lessThan(before.reputationScore.toNumber());
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await program.methods
+        .expireViolation()
+        .accounts({
+          provider: fresh.providerPda,
+          violation: stale,
+          rentPayer: reporter.publicKey,
+          cranker: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const after = await program.account.provider.fetch(fresh.providerPda);
+      expect(after.reputationScore.toNumber()).to.equal(before.reputationScore.toNumber());
+      expect(after.violationCounts.map((count) => count.toNumber())).to.deep.equal([0, 0, 0, 0, 0]);
+      expect(after.seasonViolationCounts.map((count) => count.toNumber())).to.deep.equal([0, 0, 0, 0, 0]);
+      expect(after.dismissedViolations.toNumber()).to.equal(1);
+    });
+
+    it("Pays the reporter up to the compensation cap and the rest to the treasury", async () => {
+      const fresh = await registerFreshProvider("CappedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+      const [treasuryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury")],
+        program.programId
+      );
+      const cap = 0.02 * LAMPORTS_PER_SOL;
+
+      await configure(program.methods.setMaxReporterCompensation(new anchor.BN(cap)));
+      const reporterBefore = await provider.connection.getBalance(reporter.publicKey);
+      const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+      await slashFor(fresh, freshSlaPda, violation);
+
+      // 10% of the stake is slashed; the reporter also paid the transaction fee
+      const reporterGain = (await provider.connection.getBalance(reporter.publicKey)) - reporterBefore;
+      expect(reporterGain).to.be.closeTo(cap, 10_000);
+      expect((await provider.connection.getBalance(treasuryPda)) - treasuryBefore).to.equal(
+        0.05 * LAMPORTS_PER_SOL - cap
+      );
+
+      await configure(program.methods.setMaxReporterCompensation(new anchor.BN(0)));
+    });
+
+    it("Never slashes a provider below the protected balance", async () => {
+      const fresh = await registerFreshProvider("ProtectedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const first = await reportAgainst(fresh, freshSlaPda, 1);
+      const second = await reportAgainst(fresh, freshSlaPda, 2);
+      const floor = 0.48 * LAMPORTS_PER_SOL;
+
+      // 10% would take 0.05 SOL; only the 0.02 SOL above the floor is slashable
+      await configure(program.methods.setProtectedBalance(new anchor.BN(floor)));
+      await slashFor(fresh, freshSlaPda, first);
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(floor);
+
+      // At the floor nothing is left to take, which is below any minimum slash
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(1)));
+      try {
+        await slashFor(fresh, freshSlaPda, second);
+        expect.fail("Should have thrown SlashTooSmall error");
+      } catch (error) {
+        expect(error.message).to.include("SlashTooSmall");
+      }
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(floor);
+
+      await configure(program.methods.setMinSlashAmount(new anchor.BN(0)));
+      await configure(program.methods.setProtectedBalance(new anchor.BN(0)));
+    });
+
+    it("Defers slashing a newly registered provider until its onboarding shield ends", async () => {
+      await configure(program.methods.setOnboardingShield(new anchor.BN(3600)));
+      const fresh = await registerFreshProvider("ShieldedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+
+      // Reports are still accepted while the shield is up
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+      try {
+        await slashFor(fresh, freshSlaPda, violation);
+        expect.fail("Should have thrown OnboardingShieldActive error");
+      } catch (error) {
+        expect(error.message).to.include("OnboardingShieldActive");
+      }
+      expect((await program.account.violation.fetch(violation)).isResolved).to.equal(false);
+
+      await configure(program.methods.setOnboardingShield(new anchor.BN(0)));
+      await slashFor(fresh, freshSlaPda, violation);
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(STAKE_AMOUNT * 0.9);
+    });
+
+    it("Clamps a single slash to the configured share of the stake", async () => {
+      try {
+        await configure(program.methods.setMaxSingleSlash(10001));
+        expect.fail("Should have thrown InvalidBasisPoints error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBasisPoints");
+      }
+
+      const fresh = await registerFreshProvider("ClampedAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+
+      // The SLA asks for 10%, the ceiling allows 5%
+      await configure(program.methods.setMaxSingleSlash(500));
+      await slashFor(fresh, freshSlaPda, violation);
+      await configure(program.methods.setMaxSingleSlash(10000));
+
+      expect((await program.account.provider.fetch(fresh.providerPda)).stakeAmount.toNumber()).to.equal(STAKE_AMOUNT * 0.95);
+      expect((await program.account.violation.fetch(violation)).isResolved).to.equal(true);
+    });
+
+    it("Halves recent slashing once per rolling window", async () => {
+      try {
+        await configure(program.methods.setRollingWindow(new anchor.BN(0)));
+        expect.fail("Should have thrown InvalidRollingWindow error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidRollingWindow");
+      }
+
+      const stats = () => program.methods.getProtocolStats().accounts({ protocol: protocolPda }).view();
+      const fresh = await registerFreshProvider("RollingAgent");
+      const freshSlaPda = await defineSlaFor(fresh, 10);
+      const violation = await reportAgainst(fresh, freshSlaPda, 1);
+
+      const before = (await stats()).slashedRolling.toNumber();
+      await slashFor(fresh, freshSlaPda, violation);
+      const recent = (await stats()).slashedRolling.toNumber();
+      expect(recent - before).to.equal(STAKE_AMOUNT * 0.1);
+
+      // With a 1 second window, two seconds later it has halved at least twice
+      await configure(program.methods.setRollingWindow(new anchor.BN(1)));
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      expect((await stats()).slashedRolling.toNumber()).to.be.at.most(Math.floor(recent / 4));
+
+      await configure(program.methods.setRollingWindow(new anchor.BN(7 * 24 * 60 * 60)));
+    });
+
+    it("Lets the config manager tune parameters but not administer the protocol", async () => {
+      const manager = Keypair.generate();
+      const fresh = await registerFreshProvider("ManagedAgent");
+      await configure(program.methods.setConfigManager(manager.publicKey));
+
+      await program.methods
+        .setProtectedBalance(new anchor.BN(MIN_STAKE))
+        .accounts({ protocol: protocolPda, authority: manager.publicKey })
+        .signers([manager])
+        .rpc();
+      expect((await program.account.protocol.fetch(protocolPda)).protectedBalance.toNumber()).to.equal(MIN_STAKE);
+
+      // Fees are routine parameters too, still bounded by their caps
+      const fee = 0.001 * LAMPORTS_PER_SOL;
+      await program.methods
+        .setReportFee(new anchor.BN(fee))
+        .accounts({ protocol: protocolPda, authority: manager.publicKey })
+        .signers([manager])
+        .rpc();
+      await program.methods
+        .setCrankRewards(new anchor.BN(1000), new anchor.BN(10_000), new anchor.BN(60))
+        .accounts({ protocol: protocolPda, authority: manager.publicKey })
+        .signers([manager])
+        .rpc();
+      const managed = await program.account.protocol.fetch(protocolPda);
+      expect(managed.reportFee.toNumber()).to.equal(fee);
+      expect(managed.crankReward.toNumber()).to.equal(1000);
+      expect(managed.crankBudget.toNumber()).to.equal(10_000);
+      try {
+        await program.methods
+          .setReportFee(new anchor.BN(0.02 * LAMPORTS_PER_SOL))
+          .accounts({ protocol: protocolPda, authority: manager.publicKey })
+          .signers([manager])
+          .rpc();
+        expect.fail("Should have thrown ReportFeeTooHigh error");
+      } catch (error) {
+        expect(error.message).to.include("ReportFeeTooHigh");
+      }
+
+      const adminOnly = [
+        program.methods.pauseProtocol().accounts({ protocol: protocolPda, authority: manager.publicKey }),
+        program.methods
+          .blacklistProvider()
+          .accounts({ protocol: protocolPda, provider: fresh.providerPda, authority: manager.publicKey }),
+        program.methods
+          .setMultisig([manager.publicKey], 1)
+          .accounts({ protocol: protocolPda, authority: manager.publicKey }),
+      ];
+      for (const method of adminOnly) {
+        try {
+          await method.signers([manager]).rpc();
+          expect.fail("Should have thrown Unauthorized error");
+        } catch (error) {
+          expect(error.message).to.include("Unauthorized");
+        }
+      }
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.paused).to.equal(false);
+      expect(protocolAccount.threshold).to.equal(0);
+      expect((await program.account.provider.fetch(fresh.providerPda)).status).to.not.deep.equal({ banned: {} });
+
+      await configure(program.methods.setProtectedBalance(new anchor.BN(0)));
+      await configure(program.methods.setReportFee(new anchor.BN(0)));
+      await configure(program.methods.setCrankRewards(new anchor.BN(0), new anchor.BN(0), new anchor.BN(0)));
+      await configure(program.methods.setConfigManager(PublicKey.default));
+    });
+  });
+
+  describe("Reputation Seasons", () => {
+    it("Carries over part of the season counters into a new season", async () => {
+      const before = await program.account.provider.fetch(providerPda);
+      const protocolBefore = await program.account.protocol.fetch(protocolPda);
+
+      await program.methods
+        .advanceSeason()
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      await program.methods
+        .refreshProviderMetrics()
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+        })
+        .rpc();
+
+      const after = await program.account.provider.fetch(providerPda);
+      expect(after.season).to.equal(protocolBefore.season + 1);
+      expect(after.seasonSuccesses.toNumber()).to.equal(
+        Math.floor((before.seasonSuccesses.toNumber() * protocolBefore.seasonCarryoverBps) / 10000)
+      );
+      expect(after.successfulRequests.toNumber()).to.equal(before.successfulRequests.toNumber());
+    });
+
+    it("Pays part of each slash to providers that went the season without violations", async () => {
+      const [honestPoolPda] = PublicKey.findProgramAddressSync([Buffer.from("honest_pool")], program.programId);
+      const setHonestPool = (poolBps: number) =>
+        program.methods
+          .setHonestPool(poolBps)
+          .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+          .rpc();
+      const enroll = (fresh: FreshProvider) =>
+        program.methods
+          .enrollHonestProvider()
+          .accounts({ protocol: protocolPda, authority: fresh.authority.publicKey })
+          .signers([fresh.authority])
+          .rpc();
+      const claim = (fresh: FreshProvider) =>
+        program.methods
+          .claimHonestReward()
+          .accounts({ protocol: protocolPda, authority: fresh.authority.publicKey })
+          .signers([fresh.authority])
+          .rpc();
+
+      const honest = await registerFreshProvider("HonestAgent");
+      await program.methods
+        .recordSuccess(null)
+        .accounts({ protocol: protocolPda, provider: honest.providerPda, caller: provider.wallet.publicKey })
+        .rpc();
+      const cheater = await registerFreshProvider("CheaterAgent");
+      const cheaterSlaPda = await defineSlaFor(cheater);
+      const violation = await reportAgainst(cheater, cheaterSlaPda, 1);
+
+      // Off by default
+      try {
+        await enroll(honest);
+        expect.fail("Should have thrown HonestPoolDisabled error");
+      } catch (error) {
+        expect(error.message).to.include("HonestPoolDisabled");
+      }
+
+      await setHonestPool(5000);
+      try {
+        await enroll(cheater);
+        expect.fail("Should have thrown HonestRewardIneligible error");
+      } catch (error) {
+        expect(error.message).to.include("HonestRewardIneligible");
+      }
+      await enroll(honest);
+      const weight = (await program.account.provider.fetch(honest.providerPda)).honestWeight.toNumber();
+      expect(weight).to.be.greaterThan(0);
+
+      const poolBefore = await provider.connection.getBalance(honestPoolPda);
+      await slashFor(cheater, cheaterSlaPda, violation);
+      const inflow = (await provider.connection.getBalance(honestPoolPda)) - poolBefore;
+      expect(inflow).to.be.greaterThan(0);
+      expect((await program.account.protocol.fetch(protocolPda)).honestPoolAccrued.toNumber()).to.equal(inflow);
+
+      // Nothing is claimable until the season closes
+      try {
+        await claim(honest);
+        expect.fail("Should have thrown HonestRewardIneligible error");
+      } catch (error) {
+        expect(error.message).to.include("HonestRewardIneligible");
+      }
+
+      await program.methods
+        .advanceSeason()
+        .accounts({ protocol: protocolPda, authority: provider.wallet.publicKey })
+        .rpc();
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      const expected = Math.floor(
+        (protocolAccount.honestPayout.toNumber() * weight) / protocolAccount.honestPayoutWeight.toNumber()
+      );
+      const balanceBefore = await provider.connection.getBalance(honest.authority.publicKey);
+      await claim(honest);
+      expect((await provider.connection.getBalance(honest.authority.publicKey)) - balanceBefore).to.equal(expected);
+
+      try {
+        await claim(honest);
+        expect.fail("Should have thrown HonestRewardIneligible error");
+      } catch (error) {
+        expect(error.message).to.include("HonestRewardIneligible");
+      }
+
+      await setHonestPool(0);
+    });
+  });
+
+  describe("Vault Audit", () => {
+    it("Reconciles recorded stake with a vault that received a direct transfer", async () => {
+      const surplus = 1_000_000;
+      const fundTx = new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: vaultPda,
+          lamports: surplus,
+        })
+      );
+      await provider.sendAndConfirm(fundTx);
+
+      const before = await program.account.provider.fetch(providerPda);
+
+      await program.methods
+        .auditVault(false)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          stakeVault: vaultPda,
+          authority: reporter.publicKey,
+        })
+        .signers([reporter])
+        .rpc();
+
+      let after = await program.account.provider.fetch(providerPda);
+      expect(after.stakeAmount.toNumber()).to.equal(before.stakeAmount.toNumber());
+
+      await program.methods
+        .auditVault(true)
+        .accounts({
+          protocol: protocolPda,
+          provider: providerPda,
+          stakeVault: vaultPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      after = await program.account.provider.fetch(providerPda);
+      expect(after.stakeAmount.toNumber()).to.equal(before.stakeAmount.toNumber() + surplus);
+    });
+  });
+
+  describe("Multisig Administration", () => {
+    const coSigner = Keypair.generate();
+
+    it("Migrates administration to a 2-of-2 multisig", async () => {
+      await program.methods
+        .setMultisig([provider.wallet.publicKey, coSigner.publicKey], 2)
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.threshold).to.equal(2);
+      expect(protocolAccount.authorities.length).to.equal(2);
+    });
+
+    it("Rejects admin changes without enough signers", async () => {
+      try {
+        await program.methods
+          .setStakeWarningThreshold(1000)
+          .accounts({
+            protocol: protocolPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown Unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("Accepts admin changes co-signed by the multisig", async () => {
+      await program.methods
+        .setMultisig([], 0) // Back to single-authority mode
+        .accounts({
+          protocol: protocolPda,
+          authority: provider.wallet.publicKey,
+        })
+        .remainingAccounts([{ pubkey: coSigner.publicKey, isSigner: true, isWritable: false }])
+        .signers([coSigner])
+        .rpc();
+
+      const protocolAccount = await program.account.protocol.fetch(protocolPda);
+      expect(protocolAccount.threshold).to.equal(0);
     });
   });
 });